@@ -0,0 +1,144 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Generates `src/opengl/dither/noise.rs`, the blue noise pattern used for
+// dithering, with the void and cluster method:
+//
+//     cargo run --release --example blue_noise > src/opengl/dither/noise.rs
+
+const NOISE_SIZE: usize = 64;
+const NOISE_SIGMA: f32 = 1.5;
+
+// A binary pattern wrapping around at its edges, together with how crowded
+// each pixel is by the set pixels around it
+#[derive(Clone)]
+struct Pattern {
+    set: Vec<bool>,
+    energy: Vec<f32>,
+    kernel: Vec<f32>,
+}
+
+impl Pattern {
+    fn new() -> Self {
+        let distance = |d: usize| d.min(NOISE_SIZE - d) as f32;
+        let kernel = (0..NOISE_SIZE * NOISE_SIZE)
+            .map(|index| {
+                let (x, y) = (distance(index % NOISE_SIZE), distance(index / NOISE_SIZE));
+                (-(x * x + y * y) / (2.0 * NOISE_SIGMA * NOISE_SIGMA)).exp()
+            })
+            .collect();
+        Self {
+            set: vec![false; NOISE_SIZE * NOISE_SIZE],
+            energy: vec![0.0; NOISE_SIZE * NOISE_SIZE],
+            kernel,
+        }
+    }
+
+    fn toggle(&mut self, index: usize) {
+        let sign = if self.set[index] { -1.0 } else { 1.0 };
+        self.set[index] = !self.set[index];
+        let (x0, y0) = (index % NOISE_SIZE, index / NOISE_SIZE);
+        for (other, energy) in self.energy.iter_mut().enumerate() {
+            let x = (other % NOISE_SIZE + NOISE_SIZE - x0) % NOISE_SIZE;
+            let y = (other / NOISE_SIZE + NOISE_SIZE - y0) % NOISE_SIZE;
+            *energy += sign * self.kernel[y * NOISE_SIZE + x];
+        }
+    }
+
+    fn tightest_cluster(&self) -> usize {
+        (0..self.set.len())
+            .filter(|index| self.set[*index])
+            .max_by(|a, b| self.energy[*a].total_cmp(&self.energy[*b]))
+            .expect("Cannot fail")
+    }
+
+    fn largest_void(&self) -> usize {
+        (0..self.set.len())
+            .filter(|index| !self.set[*index])
+            .min_by(|a, b| self.energy[*a].total_cmp(&self.energy[*b]))
+            .expect("Cannot fail")
+    }
+}
+
+fn generate() -> Vec<u16> {
+    let size = NOISE_SIZE * NOISE_SIZE;
+    let initial = size / 10;
+    let mut pattern = Pattern::new();
+    // Fixed pseudo random start, so that the pattern is the same on every run
+    let mut seed = 0x2545_f491u32;
+    while pattern.set.iter().filter(|set| **set).count() < initial {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let index = (seed >> 8) as usize % size;
+        if !pattern.set[index] {
+            pattern.toggle(index);
+        }
+    }
+    // Spread the start evenly by moving the tightest cluster into the largest void
+    loop {
+        let cluster = pattern.tightest_cluster();
+        pattern.toggle(cluster);
+        let void = pattern.largest_void();
+        pattern.toggle(void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; size];
+    let mut removed = pattern.clone();
+    for rank in (0..initial).rev() {
+        let cluster = removed.tightest_cluster();
+        removed.toggle(cluster);
+        ranks[cluster] = rank;
+    }
+    for rank in initial..size {
+        let void = pattern.largest_void();
+        pattern.toggle(void);
+        ranks[void] = rank;
+    }
+    ranks
+        .into_iter()
+        .map(|rank| ((2 * rank + 1) * (u16::MAX as usize + 1) / (2 * size)) as u16)
+        .collect()
+}
+
+fn main() {
+    // The generated file carries the same license as this one
+    let header: String = include_str!("blue_noise.rs")
+        .lines()
+        .take_while(|line| line.starts_with("//"))
+        .take(15)
+        .map(|line| format!("{line}\n"))
+        .collect();
+    print!("{header}");
+    println!(
+        "
+// Thresholds of a 64x64 blue noise pattern wrapping around at its edges. It
+// was generated with the void and cluster method, using a gaussian of
+// sigma {NOISE_SIGMA} and an initial pattern with every tenth pixel set. Each rank r of
+// the {} pixels is stored as (2r + 1) * 8, the center of its share of the
+// 16 bit range. Generated by `examples/blue_noise.rs`.
+
+#[rustfmt::skip]
+pub static BLUE_NOISE: [u16; {NOISE_SIZE} * {NOISE_SIZE}] = [",
+        NOISE_SIZE * NOISE_SIZE
+    );
+    for row in generate().chunks(16) {
+        let row: Vec<String> = row.iter().map(u16::to_string).collect();
+        println!("    {},", row.join(", "));
+    }
+    println!("];");
+}
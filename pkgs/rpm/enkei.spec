@@ -8,11 +8,11 @@ URL:            https://enkei.spacesnek.rocks/
 Source0:        https://codeload.github.com/jwuensche/%{name}/tar.gz/refs/tags/v%{version}
 
 %if 0%{?suse_version}
-BuildRequires:  cargo wayland-devel Mesa-libEGL-devel glib2-devel cairo-devel libwebp-devel gcc
-Requires:       Mesa-libEGL1 libglib-2_0-0 libcairo2 libcairo-gobject2 libwebp7
+BuildRequires:  cargo wayland-devel Mesa-libEGL-devel libwebp-devel gcc
+Requires:       Mesa-libEGL1 libwebp7
 %else
-BuildRequires:  cargo wayland-devel mesa-libEGL-devel glib2-devel cairo-devel cairo-gobject-devel libwebp-devel
-Requires:       mesa-libEGL glib2 cairo cairo-gobject libwebp
+BuildRequires:  cargo wayland-devel mesa-libEGL-devel libwebp-devel
+Requires:       mesa-libEGL libwebp
 %endif
 
 # I will not be bothered with this for now...
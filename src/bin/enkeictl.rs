@@ -1,4 +1,7 @@
+use chrono::{NaiveTime, Timelike};
 use clap::ArgEnum;
+use enkei::cli::{parse_offset, parse_time_of_day};
+use enkei::fill;
 use std::{io::Write, os::unix::net::UnixStream, path::PathBuf};
 
 #[derive(Clone, Debug, ArgEnum, Serialize, Deserialize)]
@@ -6,6 +9,21 @@ enum Scaling {
     Fill,
     Fit,
     None,
+    Stretch,
+    Tile,
+}
+
+#[derive(Clone, Debug, ArgEnum, Serialize, Deserialize)]
+enum Alignment {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
 }
 
 #[derive(Clone, Debug, ArgEnum, Serialize, Deserialize)]
@@ -15,12 +33,25 @@ enum Filter {
     Best,
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Seek {
+    Time(u32),
+    Offset(i64),
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Message {
     filter: Option<Filter>,
     scaling: Option<Scaling>,
-    path: PathBuf,
+    path: Option<PathBuf>,
     mode: Option<Mode>,
+    seek: Option<Seek>,
+    speed: Option<f64>,
+    color: Option<String>,
+    alignment: Option<Alignment>,
+    tile_scale: Option<f64>,
+    background: Option<String>,
+    backdrop: Option<String>,
 }
 use clap::Parser;
 use serde::Deserialize;
@@ -31,10 +62,19 @@ const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
 const DESC: &str = "Control application for enkei.";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const FILE_HELP: &str = "The path to the wallpaper to be shown. The mode, static or dynamic, gets determined automatically by default, based on the file suffix.";
+const FILE_HELP: &str = "The path to the wallpaper to be shown. The mode, static or dynamic, gets determined automatically by default, based on the file suffix. Directories are shown as a slideshow of the contained images.";
+const COLOR_HELP: &str = "Show a solid color or a gradient instead of a file. Either a color like `#1e1e2e`, or a CSS like gradient such as `linear-gradient(45deg, #1e1e2e, #89b4fa 80%)` or `radial-gradient(#89b4fa, #1e1e2e)` with up to 16 colors.";
 const MODE_HELP: &str = "The display mode, static or dynamic, to be used for the given wallpaper. Normally this gets detected automatically based on the file suffix. If this is not possible set it explicitly here.";
-const SCALE_HELP: &str = "The scaling mode, which should be used to fit the image to the screen. Fit will try to fit the whole image to the screen, while Fill will try to fill the screen completely upscaling and cropping the image if necessary.";
-const FILTER_HELP: &str = "The filter method which should be applied when a wallpaper is scaled. Variants correspond to cairo filters.";
+const SCALE_HELP: &str = "The scaling mode, which should be used to fit the image to the screen. Fit will try to fit the whole image to the screen, while Fill will try to fill the screen completely upscaling and cropping the image if necessary. Stretch fills the screen ignoring the aspect ratio, Tile repeats the image at its own size enlarged by --tile-scale, and None shows it at its own size.";
+const ALIGN_HELP: &str = "The edge or corner of the screen the image is placed at. With Fill this chooses which part of the image stays visible when it is cropped, with Fit and None where the image is shown, and with Tile where the first tile starts.";
+const BACKGROUND_HELP: &str = "What is shown where an image does not cover the whole screen. Either a color like `#1e1e2e`, `dominant` for the most common color of the image, `blur` for a blurred and darkened copy of the image, `mirror` to mirror the image at its edges, or `transparent` to show the backdrop.";
+const BACKDROP_HELP: &str = "What is shown below transparent parts of the wallpaper. Either a color like `#1e1e2e`, a gradient as given to --color, or the path of an image covering the screen.";
+const TILE_SCALE_HELP: &str =
+    "The factor by which images are enlarged before they are tiled with `--scale tile`.";
+const FILTER_HELP: &str = "The filter method which should be applied when a wallpaper is scaled. Fast uses a bilinear, Good a Catmull-Rom and Best a Lanczos filter.";
+const TIME_HELP: &str = "Jump to the given local time of today, formatted as HH:MM or HH:MM:SS. Time keeps passing from there on.";
+const OFFSET_HELP: &str = "Jump to the given time relative to the current time. Either a number of seconds or a combination of hours, minutes and seconds like `+1h30m`, `-2h` or `90s`. An offset of 0 returns to the current time.";
+const SPEED_HELP: &str = "How many times faster than real time the wallpaper is played from now on. A speed of 1 returns to real time.";
 
 #[derive(clap::Parser, Debug)]
 #[clap(
@@ -49,9 +89,18 @@ pub struct Args {
         help = "The file to display.",
         long_help = FILE_HELP,
         takes_value = true,
-        required = true,
+        required_unless_present_any = &["time", "offset", "speed", "color", "align", "tile-scale", "background", "backdrop"],
     )]
-    file: PathBuf,
+    file: Option<PathBuf>,
+    #[clap(
+        long = "color",
+        help = "A color or gradient to display instead of a file.",
+        long_help = COLOR_HELP,
+        takes_value = true,
+        value_name = "SPEC",
+        conflicts_with = "file",
+    )]
+    color: Option<fill::Fill>,
     #[clap(
         arg_enum,
         short = 'f',
@@ -72,6 +121,49 @@ pub struct Args {
         ignore_case = true,
     )]
     scale: Option<Scaling>,
+    #[clap(
+        arg_enum,
+        long = "align",
+        help = "Where to place images on the screen.",
+        long_help = ALIGN_HELP,
+        takes_value = true,
+        ignore_case = true,
+    )]
+    align: Option<Alignment>,
+    #[clap(
+        long = "tile-scale",
+        help = "Factor by which tiled images are enlarged.",
+        long_help = TILE_SCALE_HELP,
+        takes_value = true,
+        validator = |value: &str| match value.parse::<f64>() {
+            Ok(scale) if scale > 0.0 => Ok(()),
+            _ => Err("The tile scale has to be a positive number"),
+        },
+    )]
+    tile_scale: Option<f64>,
+    #[clap(
+        long = "background",
+        help = "What to show around images not covering the screen.",
+        long_help = BACKGROUND_HELP,
+        takes_value = true,
+        value_name = "SPEC",
+        validator = |value: &str| match value {
+            "dominant" | "blur" | "mirror" | "transparent" => Ok(()),
+            color => fill::color(color)
+                .map(|_| ())
+                .map_err(|e| format!("{e}, or one of dominant, blur, mirror or transparent")),
+        },
+    )]
+    background: Option<String>,
+    #[clap(
+        long = "backdrop",
+        help = "What to show below transparent parts of the wallpaper.",
+        long_help = BACKDROP_HELP,
+        takes_value = true,
+        value_name = "SPEC",
+        parse(try_from_str = parse_backdrop),
+    )]
+    backdrop: Option<String>,
     #[clap(
         arg_enum,
         short = 'm',
@@ -82,6 +174,35 @@ pub struct Args {
         ignore_case = true,
     )]
     mode: Option<Mode>,
+    #[clap(
+        long = "time",
+        help = "Jump to this time of day.",
+        long_help = TIME_HELP,
+        takes_value = true,
+        conflicts_with = "offset",
+        parse(try_from_str = parse_time_of_day),
+    )]
+    time: Option<NaiveTime>,
+    #[clap(
+        long = "offset",
+        help = "Jump to this time relative to now.",
+        long_help = OFFSET_HELP,
+        takes_value = true,
+        allow_hyphen_values = true,
+        parse(try_from_str = parse_offset),
+    )]
+    offset: Option<i64>,
+    #[clap(
+        long = "speed",
+        help = "Factor by which the shown time passes faster.",
+        long_help = SPEED_HELP,
+        takes_value = true,
+        validator = |value: &str| match value.parse::<f64>() {
+            Ok(speed) if speed > 0.0 => Ok(()),
+            _ => Err("The speed has to be a positive number"),
+        },
+    )]
+    speed: Option<f64>,
 }
 
 #[derive(ArgEnum, Clone, Debug, Serialize, Deserialize)]
@@ -90,29 +211,57 @@ pub enum Mode {
     Dynamic,
 }
 
+// Paths are made absolute, as enkei runs in another directory
+fn parse_backdrop(spec: &str) -> Result<String, String> {
+    if fill::Fill::is_fill(spec) {
+        spec.parse::<fill::Fill>().map(|fill| fill.to_string())
+    } else {
+        std::fs::canonicalize(spec)
+            .map(|path| path.to_string_lossy().into_owned())
+            .map_err(|_| format!("No file found at `{spec}`"))
+    }
+}
+
 fn main() {
     let args = Args::parse();
 
-    if !args.file.exists() {
-        eprintln!("Path {:?} does not exist.", args.file);
-        std::process::exit(1);
-    }
+    let path = match &args.file {
+        Some(file) if !file.exists() => {
+            eprintln!("Path {:?} does not exist.", file);
+            std::process::exit(1);
+        }
+        Some(file) => match file.canonicalize() {
+            Ok(abs) => Some(abs),
+            Err(_) => {
+                eprintln!("Could not normalize path {:?}", file);
+                std::process::exit(3);
+            }
+        },
+        None => None,
+    };
 
-    if let Ok(abs) = args.file.canonicalize() {
-        let msg = Message {
-            filter: args.filter,
-            scaling: args.scale,
-            path: abs,
-            mode: args.mode,
-        };
+    let seek = match (args.time, args.offset) {
+        (Some(time), _) => Some(Seek::Time(time.num_seconds_from_midnight())),
+        (None, Some(offset)) => Some(Seek::Offset(offset)),
+        (None, None) => None,
+    };
+    let msg = Message {
+        filter: args.filter,
+        scaling: args.scale,
+        path,
+        mode: args.mode,
+        seek,
+        speed: args.speed,
+        color: args.color.map(|fill| fill.to_string()),
+        alignment: args.align,
+        tile_scale: args.tile_scale,
+        background: args.background,
+        backdrop: args.backdrop,
+    };
 
-        if write(msg).is_err() {
-            eprintln!("Could not connect to enkei. Please make sure that $XDG_RUNTIME_DIR is set and enkei is running.");
-            std::process::exit(2);
-        }
-    } else {
-        eprintln!("Could not normalize path {:?}", args.file);
-        std::process::exit(3);
+    if write(msg).is_err() {
+        eprintln!("Could not connect to enkei. Please make sure that $XDG_RUNTIME_DIR is set and enkei is running.");
+        std::process::exit(2);
     }
 }
 
@@ -0,0 +1,270 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// `enkei check` reads a wallpaper like the daemon would and reports everything
+// which would make it fail or misbehave later on, without connecting to wayland.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
+
+use crate::image::scaling::{Filter, Placement};
+use crate::image::Image;
+use crate::metadata::{
+    calendar_active, solar_schedule, Clock, Metadata, MetadataReader, Settings, Source, Transition,
+};
+use crate::{ApplicationError, Mode};
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Returns the exit code of the process, non-zero if any problem has been found.
+pub fn run(path: &PathBuf, mode: &Option<Mode>, settings: &Settings) -> i32 {
+    let result = if is_description(path, mode) {
+        MetadataReader::read_checked(path, settings).map_err(ApplicationError::from)
+    } else {
+        crate::get_metadata_for_path(mode, path, settings).map(|metadata| (metadata, Vec::new()))
+    };
+    let (metadata, mut problems) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            println!("error: {e}");
+            return 1;
+        }
+    };
+
+    print_metadata(&metadata, "");
+    problems.extend(unreadable(&metadata));
+
+    println!();
+    // The timeline is still shown in cycles from its start, this is no problem
+    if let Some(note) = start_time(&metadata) {
+        println!("note: {note}");
+    }
+    for problem in problems.iter() {
+        println!("error: {problem}");
+    }
+    if problems.is_empty() {
+        println!("No problems found.");
+        0
+    } else {
+        println!("{} problem(s) found.", problems.len());
+        1
+    }
+}
+
+// Only descriptions carry information beyond the images themselves
+fn is_description(path: &Path, mode: &Option<Mode>) -> bool {
+    match mode {
+        Some(Mode::Static) => false,
+        Some(Mode::Dynamic) => !crate::is_heic(path),
+        None => MetadataReader::is_description(path),
+    }
+}
+
+fn print_metadata(metadata: &Metadata, indent: &str) {
+    match metadata {
+        Metadata::Timeline {
+            start_time,
+            image_transisitons,
+            total_duration_sec,
+            clock,
+        } => {
+            let cycle_start = current_cycle(start_time, *total_duration_sec, clock);
+            if *total_duration_sec >= u32::MAX as f64 {
+                println!("{indent}Static image:");
+            } else {
+                println!(
+                    "{indent}Timeline of {} entries repeating every {}, current cycle:",
+                    image_transisitons.len(),
+                    seconds(*total_duration_sec)
+                );
+            }
+            for transition in image_transisitons {
+                let begin = cycle_start + transition.time_range().start;
+                print_transition(
+                    transition,
+                    |offset| clock.moment(start_time, millis(offset)),
+                    begin,
+                    indent,
+                );
+            }
+        }
+        Metadata::Solar { frames, location } => {
+            println!(
+                "{indent}Following the sun at latitude {}, longitude {}, today:",
+                location.latitude, location.longitude
+            );
+            let today = chrono::Utc::now().naive_utc().date();
+            for (time, frame) in solar_schedule(frames, location, today) {
+                println!(
+                    "{indent}  {}  {:<10}  {}",
+                    time.with_timezone(&Local).format(TIME_FORMAT),
+                    "solar",
+                    frame.file
+                );
+            }
+        }
+        Metadata::Slideshow(slideshow) => {
+            println!(
+                "{indent}Slideshow of {} images every {} in {:?} order:",
+                slideshow.images().len(),
+                seconds(slideshow.settings().interval),
+                slideshow.settings().order
+            );
+            for image in slideshow.images() {
+                println!("{indent}  {}", image.to_string_lossy());
+            }
+        }
+        Metadata::Themed { light, dark, .. } => {
+            println!("{indent}Light variant:");
+            print_metadata(light, &format!("{indent}  "));
+            println!("{indent}Dark variant:");
+            print_metadata(dark, &format!("{indent}  "));
+        }
+        Metadata::Calendar(sets) => {
+            let today = Local::now().date().naive_local();
+            println!(
+                "{indent}Calendar of {} sets, today set #{} is shown:",
+                sets.len(),
+                calendar_active(sets, today) + 1
+            );
+            for (idx, set) in sets.iter().enumerate() {
+                println!("{indent}Set #{} ({set}):", idx + 1);
+                print_metadata(&set.wallpaper, &format!("{indent}  "));
+            }
+        }
+    }
+}
+
+// `moment` turns seconds since the start of the timeline into local time
+fn print_transition<F: Fn(f64) -> Option<DateTime<Local>>>(
+    transition: &Transition,
+    moment: F,
+    begin: f64,
+    indent: &str,
+) {
+    let format = |time: Option<DateTime<Local>>| {
+        time.map(|time| time.format(TIME_FORMAT).to_string())
+            .unwrap_or_else(|| "(skipped)".into())
+    };
+    println!(
+        "{indent}  {}  {:<10}  {:>10}  {}",
+        format(moment(begin)),
+        "static",
+        seconds(transition.duration_static()),
+        transition.from()
+    );
+    if let (Some(to), Some(kind)) = (transition.to(), transition.kind()) {
+        println!(
+            "{indent}  {}  {:<10}  {:>10}  {} -> {} ({})",
+            format(moment(begin + transition.duration_static())),
+            "transition",
+            seconds(transition.duration_transition()),
+            transition.from(),
+            to,
+            kind.name()
+        );
+    }
+}
+
+// Seconds from the start until the cycle containing the current moment
+fn current_cycle(start_time: &NaiveDateTime, total_duration_sec: f64, clock: &Clock) -> f64 {
+    let elapsed = clock.elapsed(start_time, Utc::now());
+    if elapsed > total_duration_sec && total_duration_sec > 0.0 {
+        (elapsed / total_duration_sec).floor() * total_duration_sec
+    } else {
+        0.0
+    }
+}
+
+// Static images are shown for an unrepresentable duration
+fn millis(seconds: f64) -> Duration {
+    Duration::milliseconds((seconds * 1000.0).min(i64::MAX as f64 / 2.0) as i64)
+}
+
+fn seconds(duration: f64) -> String {
+    if duration >= u32::MAX as f64 {
+        "forever".into()
+    } else {
+        format!("{duration}s")
+    }
+}
+
+fn start_time(metadata: &Metadata) -> Option<String> {
+    match metadata {
+        Metadata::Timeline { start_time, .. } if *start_time > Local::now().naive_local() => {
+            Some(format!("The start time {start_time} lies in the future"))
+        }
+        Metadata::Themed { light, dark, .. } => start_time(light).or_else(|| start_time(dark)),
+        Metadata::Calendar(sets) => sets.iter().find_map(|set| start_time(&set.wallpaper)),
+        _ => None,
+    }
+}
+
+// All images together with the entry of the description giving them
+fn paths(metadata: &Metadata) -> Vec<(&PathBuf, Option<&str>)> {
+    match metadata {
+        Metadata::Timeline {
+            image_transisitons, ..
+        } => image_transisitons
+            .iter()
+            .flat_map(|transition| std::iter::once(transition.from()).chain(transition.to()))
+            .flat_map(with_entry)
+            .collect(),
+        Metadata::Solar { frames, .. } => frames
+            .iter()
+            .flat_map(|frame| with_entry(&frame.file))
+            .collect(),
+        Metadata::Themed { light, dark, .. } => {
+            let mut all = paths(light);
+            all.extend(paths(dark));
+            all
+        }
+        Metadata::Slideshow(slideshow) => {
+            slideshow.images().iter().map(|path| (path, None)).collect()
+        }
+        Metadata::Calendar(sets) => sets.iter().flat_map(|set| paths(&set.wallpaper)).collect(),
+    }
+}
+
+fn with_entry(source: &Source) -> Vec<(&PathBuf, Option<&str>)> {
+    source.paths().map(|path| (path, source.entry())).collect()
+}
+
+// Every missing or broken image is listed, not only the first one. Images
+// given more than once are named with their first entry.
+fn unreadable(metadata: &Metadata) -> Vec<String> {
+    let mut entries: BTreeMap<&PathBuf, Option<&str>> = BTreeMap::new();
+    for (path, entry) in paths(metadata) {
+        entries.entry(path).or_insert(entry);
+    }
+    entries
+        .into_iter()
+        .filter_map(|(path, entry)| {
+            let given = entry
+                .map(|entry| format!(" given in {entry}"))
+                .unwrap_or_default();
+            if !path.exists() {
+                return Some(format!("The image {:?}{given} does not exist", path));
+            }
+            // Corrections never fail, decoding is enough to find broken images
+            Image::new(path.clone(), Placement::default(), Filter::Good, true)
+                .err()
+                .map(|e| format!("Could not decode {:?}{given}: {e}", path))
+        })
+        .collect()
+}
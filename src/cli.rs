@@ -0,0 +1,48 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Parsers of command line arguments understood by both enkei and enkeictl.
+
+use chrono::NaiveTime;
+use lazy_regex::regex_captures;
+
+pub fn parse_time_of_day(time: &str) -> Result<NaiveTime, chrono::ParseError> {
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+}
+
+// Either plain seconds or a combination like `1h30m`, optionally signed
+pub fn parse_offset(offset: &str) -> Result<i64, String> {
+    match offset.strip_prefix('-') {
+        Some(unsigned) => parse_duration(unsigned).map(|secs| -secs),
+        None => parse_duration(offset.strip_prefix('+').unwrap_or(offset)),
+    }
+}
+
+pub fn parse_duration(duration: &str) -> Result<i64, String> {
+    if let Ok(secs) = duration.parse::<u32>() {
+        return Ok(secs as i64);
+    }
+    match regex_captures!(r"^(?:(\d+)h)?(?:(\d+)m)?(?:(\d+)s)?$", duration) {
+        Some((_, hours, minutes, seconds)) if !duration.is_empty() => {
+            let part = |value: &str| value.parse::<i64>().unwrap_or(0);
+            Ok(part(hours) * 3600 + part(minutes) * 60 + part(seconds))
+        }
+        _ => Err(format!(
+            "Invalid duration `{duration}`, expected seconds or a combination like `1h30m`"
+        )),
+    }
+}
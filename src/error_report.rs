@@ -29,7 +29,7 @@ type SharedOutputs = Rc<RwLock<Vec<Rc<RwLock<Output>>>>>;
 // I will try to reproduce some more usable for the user to interpret.
 pub struct ErrorReport {
     error: ApplicationError,
-    metadata: Option<Metadata>,
+    metadata: Option<Box<Metadata>>,
     outputs: Option<SharedOutputs>,
 }
 
@@ -48,7 +48,7 @@ impl ErrorReport {
     }
 
     pub fn with_metadata(mut self, meta: Metadata) -> Self {
-        self.metadata = Some(meta);
+        self.metadata = Some(Box::new(meta));
         self
     }
 
@@ -90,9 +90,10 @@ use std::fmt::Debug;
 impl Debug for ErrorReport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.error {
-            ApplicationError::InvalidDataType | ApplicationError::NotAFile(_) => {
-                self.marginal_error(f)
-            }
+            ApplicationError::InvalidDataType
+            | ApplicationError::NotAFile(_)
+            | ApplicationError::UnreadableImage(..)
+            | ApplicationError::MetadataError(MetadataError::Invalid(_)) => self.marginal_error(f),
             _ => self.fmt(f),
         }
     }
@@ -0,0 +1,229 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Solid colors and gradients shown instead of an image. They are written like
+// in CSS, e.g. `#1e1e2e`, `linear-gradient(135deg, #1e1e2e, #89b4fa 80%)` or
+// `radial-gradient(#f5e0dc, #1e1e2e)`, and drawn by the GPU at the resolution
+// of each output.
+//
+// Part of the library as `enkeictl` uses it to validate colors.
+use std::fmt;
+use std::str::FromStr;
+
+/// The most color stops a gradient may have, the shader holds them in a fixed size array.
+pub const MAX_STOPS: usize = 16;
+
+/// A solid color or gradient drawn in place of an image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub shape: Shape,
+    pub stops: Vec<Stop>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// Direction in degrees clockwise from upwards, like CSS.
+    Linear(f32),
+    /// Circles around the center, reaching the corners at the last stop.
+    Radial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stop {
+    /// Red, green and blue in the range 0 to 1.
+    pub color: [f32; 3],
+    /// Position along the gradient in the range 0 to 1.
+    pub offset: f32,
+}
+
+impl Fill {
+    /// Whether the text is meant as a fill rather than a path. Gradients may
+    /// still be invalid, paths like `#notes.png` are no color.
+    pub fn is_fill(spec: &str) -> bool {
+        let spec = spec.trim();
+        color(spec).is_ok()
+            || spec.starts_with("linear-gradient(")
+            || spec.starts_with("radial-gradient(")
+    }
+}
+
+impl FromStr for Fill {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let spec = spec.trim();
+        if spec.starts_with('#') {
+            return Ok(Fill {
+                shape: Shape::Linear(180.0),
+                stops: vec![Stop {
+                    color: color(spec)?,
+                    offset: 0.0,
+                }],
+            });
+        }
+        let (shape, args) = if let Some(mut args) = function(spec, "linear-gradient") {
+            match direction(args[0]) {
+                Some(angle) => {
+                    args.remove(0);
+                    (Shape::Linear(angle?), args)
+                }
+                None => (Shape::Linear(180.0), args),
+            }
+        } else if let Some(args) = function(spec, "radial-gradient") {
+            (Shape::Radial, args)
+        } else {
+            return Err(format!(
+                "Invalid color `{spec}`, expected `#rrggbb`, `linear-gradient(...)` or `radial-gradient(...)`"
+            ));
+        };
+        if args.len() < 2 || args.len() > MAX_STOPS {
+            return Err(format!(
+                "A gradient needs between 2 and {MAX_STOPS} colors, found {}",
+                args.len()
+            ));
+        }
+        let stops = args
+            .iter()
+            .map(|arg| stop(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Fill {
+            shape,
+            stops: distribute(stops),
+        })
+    }
+}
+
+// The comma separated arguments of `name(...)`
+fn function<'a>(spec: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let args = spec
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    Some(args.split(',').map(str::trim).collect())
+}
+
+// `None` if the argument is no direction but already the first color
+fn direction(arg: &str) -> Option<Result<f32, String>> {
+    if let Some(side) = arg.strip_prefix("to ") {
+        return Some(match side.trim() {
+            "top" => Ok(0.0),
+            "right" => Ok(90.0),
+            "bottom" => Ok(180.0),
+            "left" => Ok(270.0),
+            other => Err(format!(
+                "Invalid direction `to {other}`, expected top, right, bottom or left"
+            )),
+        });
+    }
+    let degrees = arg.strip_suffix("deg")?;
+    Some(
+        degrees
+            .trim()
+            .parse::<f32>()
+            .map_err(|_| format!("Invalid angle `{arg}`, expected degrees like `135deg`")),
+    )
+}
+
+// A color optionally followed by its position in percent
+fn stop(arg: &str) -> Result<(Option<f32>, [f32; 3]), String> {
+    let mut parts = arg.split_whitespace();
+    let color = color(parts.next().unwrap_or_default())?;
+    let offset = parts
+        .next()
+        .map(|position| {
+            position
+                .strip_suffix('%')
+                .and_then(|percent| percent.parse::<f32>().ok())
+                .map(|percent| (percent / 100.0).clamp(0.0, 1.0))
+                .ok_or_else(|| format!("Invalid position `{position}`, expected a percentage"))
+        })
+        .transpose()?;
+    if let Some(extra) = parts.next() {
+        return Err(format!("Unexpected `{extra}` in color stop `{arg}`"));
+    }
+    Ok((offset, color))
+}
+
+/// Read a color given as `#rgb` or `#rrggbb`.
+pub fn color(hex: &str) -> Result<[f32; 3], String> {
+    let invalid = || format!("Invalid color `{hex}`, expected `#rgb` or `#rrggbb`");
+    let digits = hex.strip_prefix('#').ok_or_else(invalid)?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).expect("Checked to be hex");
+    let channels = match digits.len() {
+        3 => [0, 1, 2].map(|idx| channel(&digits[idx..=idx]) * 17),
+        6 => [0, 2, 4].map(|idx| channel(&digits[idx..idx + 2])),
+        _ => return Err(invalid()),
+    };
+    Ok(channels.map(|channel| channel as f32 / 255.0))
+}
+
+// Stops without a position are spread evenly between their neighbours, as in CSS
+fn distribute(stops: Vec<(Option<f32>, [f32; 3])>) -> Vec<Stop> {
+    let last = stops.len() - 1;
+    let mut offsets: Vec<Option<f32>> = stops.iter().map(|(offset, _)| *offset).collect();
+    offsets[0] = offsets[0].or(Some(0.0));
+    offsets[last] = offsets[last].or(Some(1.0));
+    let mut prev = 0;
+    for idx in 1..=last {
+        if let Some(offset) = offsets[idx] {
+            let start = offsets[prev].expect("Set in an earlier iteration");
+            for (step, missing) in offsets[prev + 1..idx].iter_mut().enumerate() {
+                let share = (step + 1) as f32 / (idx - prev) as f32;
+                *missing = Some(start + (offset - start) * share);
+            }
+            prev = idx;
+        }
+    }
+    // Positions never go backwards
+    let mut max = 0f32;
+    stops
+        .into_iter()
+        .zip(offsets)
+        .map(|((_, color), offset)| {
+            max = max.max(offset.expect("All offsets are set"));
+            Stop { color, offset: max }
+        })
+        .collect()
+}
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |stop: &Stop| {
+            let [r, g, b] = stop.color.map(|channel| (channel * 255.0).round() as u8);
+            format!("#{r:02x}{g:02x}{b:02x}")
+        };
+        if let [stop] = self.stops.as_slice() {
+            return f.write_str(&hex(stop));
+        }
+        match self.shape {
+            Shape::Linear(angle) => write!(f, "linear-gradient({angle}deg")?,
+            Shape::Radial => write!(f, "radial-gradient(")?,
+        }
+        for (idx, stop) in self.stops.iter().enumerate() {
+            if idx > 0 || matches!(self.shape, Shape::Linear(_)) {
+                f.write_str(", ")?;
+            }
+            // Hundredths of a percent hide the imprecision of the stored fraction
+            let percent = (stop.offset * 10000.0).round() / 100.0;
+            write!(f, "{} {percent}%", hex(stop))?;
+        }
+        f.write_str(")")
+    }
+}
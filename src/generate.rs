@@ -0,0 +1,218 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// `enkei generate` writes a Gnome dynamic wallpaper description for a list of
+// images, either spread evenly over a period or shown at given times of day.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Local, NaiveTime, Timelike};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::metadata::{scan_directory, MetadataError, MetadataReader, Settings};
+use crate::schema::gnome_xml::{Background, File, Image};
+
+const DAY_SEC: f64 = 86400.0;
+
+#[derive(Error, Debug)]
+pub enum GenerateError {
+    #[error("No images given.")]
+    NoImages,
+    #[error("`{0:?}` is not an image or directory.")]
+    NotAnImage(PathBuf),
+    #[error("{0} times of day were given for {1} images, one per image is required.")]
+    AnchorCount(usize, usize),
+    #[error("The time of day {0} does not follow the preceding one within one day.")]
+    AnchorOrder(NaiveTime),
+    #[error("The period has to be positive.")]
+    InvalidPeriod,
+    #[error("The transition of {0}s does not fit into the {1}s image #{2} is shown.")]
+    TransitionTooLong(f64, f64, usize),
+    #[error("{0}")]
+    Metadata(#[from] MetadataError),
+    #[error("Could not serialize the description: {0}")]
+    Serialize(#[from] quick_xml::SeError),
+    #[error("Could not write `{0:?}`: {1}")]
+    Write(PathBuf, std::io::Error),
+}
+
+/// How the shown images are distributed over the day.
+pub enum Timing {
+    /// All images are shown equally long, the first one fully at `start`.
+    Period { start: NaiveTime, period: f64 },
+    /// Each image is fully shown at its time of day.
+    Anchors(Vec<NaiveTime>),
+}
+
+pub struct Options {
+    pub timing: Timing,
+    /// Length of the transition into the next image, half of the time each
+    /// image is shown if not given.
+    pub transition: Option<f64>,
+    pub kind: String,
+    pub output: Option<PathBuf>,
+}
+
+/// Returns the exit code of the process, non-zero if the description could not be written.
+pub fn run(images: &[PathBuf], options: &Options, settings: &Settings) -> i32 {
+    match generate(images, options, settings) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: {e}");
+            1
+        }
+    }
+}
+
+fn generate(
+    images: &[PathBuf],
+    options: &Options,
+    settings: &Settings,
+) -> Result<(), GenerateError> {
+    let images = expand(images, settings)?;
+    let (start, slots) = slots(&options.timing, images.len())?;
+    let base = options
+        .output
+        .as_ref()
+        .map(|output| match output.parent() {
+            Some(parent) if parent != Path::new("") => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        })
+        .map(|parent| parent.canonicalize().unwrap_or(parent));
+
+    let today = Local::now().date();
+    let mut blocks = vec![Image::StartTime {
+        year: today.year() as u32,
+        month: today.month(),
+        day: today.day(),
+        hour: start.hour(),
+        minute: start.minute(),
+        second: start.second(),
+    }];
+    for (idx, slot) in slots.iter().enumerate() {
+        let from = file(&images[idx], base.as_deref());
+        let to = file(&images[(idx + 1) % images.len()], base.as_deref());
+        let transition = if images.len() > 1 {
+            options.transition.unwrap_or(slot / 2.0)
+        } else {
+            0.0
+        };
+        if transition >= *slot {
+            return Err(GenerateError::TransitionTooLong(transition, *slot, idx + 1));
+        }
+        blocks.push(Image::Static {
+            duration: slot - transition,
+            file: from.clone(),
+        });
+        if transition > 0.0 {
+            blocks.push(Image::Transition {
+                kind: options.kind.clone(),
+                duration: transition,
+                from,
+                to,
+            });
+        }
+    }
+
+    let mut xml = String::new();
+    let mut serializer = quick_xml::se::Serializer::with_root(&mut xml, Some("background"))?;
+    serializer.indent(' ', 2);
+    Background { images: blocks }.serialize(serializer)?;
+    xml.push('\n');
+
+    // Everything written has to be readable again, relative paths of standard
+    // output are read from the current directory
+    let path = options
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("<stdout>"));
+    MetadataReader::read_content(&path, &xml, settings)?;
+    match &options.output {
+        Some(output) => {
+            std::fs::write(output, xml).map_err(|e| GenerateError::Write(output.clone(), e))?
+        }
+        None => print!("{xml}"),
+    }
+    Ok(())
+}
+
+// Directories are replaced by the images they contain
+fn expand(paths: &[PathBuf], settings: &Settings) -> Result<Vec<PathBuf>, GenerateError> {
+    let mut images = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            images.extend(scan_directory(path, &settings.slideshow)?);
+        } else if path.is_file() && crate::image::is_supported(path) {
+            images.push(path.clone());
+        } else {
+            return Err(GenerateError::NotAnImage(path.clone()));
+        }
+    }
+    if images.is_empty() {
+        return Err(GenerateError::NoImages);
+    }
+    Ok(images)
+}
+
+/// Start of the cycle and the time between each image and the next one.
+fn slots(timing: &Timing, count: usize) -> Result<(NaiveTime, Vec<f64>), GenerateError> {
+    match timing {
+        Timing::Period { start, period } => {
+            if *period <= 0.0 {
+                return Err(GenerateError::InvalidPeriod);
+            }
+            Ok((*start, vec![period / count as f64; count]))
+        }
+        Timing::Anchors(anchors) => {
+            if anchors.len() != count {
+                return Err(GenerateError::AnchorCount(anchors.len(), count));
+            }
+            let first = anchors[0];
+            // Seconds since the first anchor, later anchors may lie past midnight
+            let offsets = anchors
+                .iter()
+                .map(|anchor| (*anchor - first).num_seconds().rem_euclid(DAY_SEC as i64) as f64)
+                .chain(std::iter::once(DAY_SEC))
+                .collect::<Vec<_>>();
+            for idx in 1..count {
+                if offsets[idx] <= offsets[idx - 1] {
+                    return Err(GenerateError::AnchorOrder(anchors[idx]));
+                }
+            }
+            Ok((
+                first,
+                offsets
+                    .windows(2)
+                    .map(|window| window[1] - window[0])
+                    .collect(),
+            ))
+        }
+    }
+}
+
+fn file(image: &Path, base: Option<&Path>) -> File {
+    let absolute = image.canonicalize().unwrap_or_else(|_| image.into());
+    // Descriptions next to their images stay valid when moved together
+    let path = base
+        .and_then(|base| absolute.strip_prefix(base).ok())
+        .map(Path::to_path_buf)
+        .unwrap_or(absolute);
+    File {
+        path: Some(path),
+        sizes: Vec::new(),
+    }
+}
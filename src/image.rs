@@ -14,9 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod animation;
+mod correction;
 pub mod error;
+pub mod heic;
 mod images;
+mod jxl;
 pub mod scaling;
+mod svg;
 mod webp;
 
+pub use animation::Timing;
 pub use images::*;
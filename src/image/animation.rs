@@ -0,0 +1,171 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fs::File, io::BufReader, path::Path};
+
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    AnimationDecoder, DynamicImage, ImageFormat,
+};
+use log::{debug, warn};
+
+use super::error::ImageError;
+
+// Browsers show frames with shorter delays for 100ms, most animations rely on that
+const MIN_DELAY: f64 = 0.02;
+const DEFAULT_DELAY: f64 = 0.1;
+
+/// Animations taking more bytes than this, either as decoded or once scaled to
+/// an output, are shown by their first frame only.
+pub const MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+
+/// Whether `frames` frames of the given size exceed the [`MEMORY_LIMIT`].
+pub fn exceeds_limit(frames: usize, width: u32, height: u32) -> bool {
+    frames > 1 && frames * width as usize * height as usize * 4 > MEMORY_LIMIT
+}
+
+/// Keep only the first frame of an animation exceeding the [`MEMORY_LIMIT`].
+pub fn first_frame(path: &Path, mut frames: Vec<DynamicImage>) -> (Vec<DynamicImage>, Timing) {
+    warn!(
+        "Showing only the first frame of {:?}, the animation takes more than {}MiB",
+        path,
+        MEMORY_LIMIT / 1024 / 1024
+    );
+    frames.truncate(1);
+    (frames, Timing::still())
+}
+
+/// How long each frame of an image is shown. Still images consist of a single frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    delays: Vec<f64>,
+    // How often the image itself asks to be played, forever if `None`
+    loops: Option<u32>,
+}
+
+impl Timing {
+    pub fn still() -> Self {
+        Self {
+            delays: vec![0.0],
+            loops: None,
+        }
+    }
+
+    /// Build the timing from the delays in seconds of each frame.
+    pub fn new<I: IntoIterator<Item = f64>>(delays: I) -> Self {
+        let delays: Vec<f64> = delays
+            .into_iter()
+            .map(|delay| {
+                if delay < MIN_DELAY {
+                    DEFAULT_DELAY
+                } else {
+                    delay
+                }
+            })
+            .collect();
+        if delays.is_empty() {
+            return Self::still();
+        }
+        Self {
+            delays,
+            loops: None,
+        }
+    }
+
+    /// Use the loop count stored in the image.
+    pub fn with_loops(mut self, loops: Option<u32>) -> Self {
+        self.loops = loops;
+        self
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.delays.len() > 1
+    }
+
+    /// Length of one run through all frames in seconds.
+    pub fn duration(&self) -> f64 {
+        self.delays.iter().sum()
+    }
+
+    /// The shortest time a frame is shown, no redraw is needed more often than this.
+    pub fn shortest(&self) -> f64 {
+        self.delays.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    /// Whether the animation still runs after playing for `position` seconds.
+    /// Animations repeat `loops` times, or as often as the image asks for if
+    /// none is given, and then stop at their last frame.
+    pub fn is_playing(&self, position: f64, loops: Option<u32>) -> bool {
+        self.is_animated()
+            && loops
+                .or(self.loops)
+                .is_none_or(|loops| position < self.duration() * loops as f64)
+    }
+
+    /// The frame shown after playing for `position` seconds, see [Timing::is_playing].
+    pub fn frame_at(&self, position: f64, loops: Option<u32>) -> usize {
+        if !self.is_animated() {
+            return 0;
+        }
+        if !self.is_playing(position, loops) {
+            return self.delays.len() - 1;
+        }
+        let duration = self.duration();
+        let mut remaining = position.rem_euclid(duration);
+        for (idx, delay) in self.delays.iter().enumerate() {
+            if remaining < *delay {
+                return idx;
+            }
+            remaining -= delay;
+        }
+        self.delays.len() - 1
+    }
+}
+
+/// Decode all frames of an animated GIF or APNG. Returns `None` for other
+/// formats, which are decoded as still images.
+pub fn open(path: &Path) -> Result<Option<(Vec<DynamicImage>, Timing)>, ImageError> {
+    let frames = match ImageFormat::from_path(path) {
+        Ok(ImageFormat::Gif) => GifDecoder::new(BufReader::new(File::open(path)?))?.into_frames(),
+        Ok(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(BufReader::new(File::open(path)?))?;
+            if !decoder.is_apng() {
+                return Ok(None);
+            }
+            decoder.apng().into_frames()
+        }
+        _ => return Ok(None),
+    };
+    // Frames are decoded one by one to stop as soon as the limit is reached
+    let mut images = Vec::new();
+    let mut delays = Vec::new();
+    for frame in frames {
+        let frame = frame?;
+        let (width, height) = frame.buffer().dimensions();
+        if exceeds_limit(images.len() + 1, width, height) {
+            return Ok(Some(first_frame(path, images)));
+        }
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        delays.push(numer as f64 / denom.max(1) as f64 / 1000.0);
+        images.push(DynamicImage::ImageRgba8(frame.into_buffer()));
+    }
+    debug!(
+        "Decoded animation {{ path: {:?}, frames: {} }}",
+        path,
+        images.len()
+    );
+    Ok(Some((images, Timing::new(delays))))
+}
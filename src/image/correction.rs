@@ -0,0 +1,189 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Cameras and phones store pixels as recorded by the sensor, together with
+// hints how to show them. These are applied once when an image is loaded.
+use std::{fs::File, io::BufReader, path::Path};
+
+use image::{
+    codecs::{jpeg::JpegDecoder, png::PngDecoder, tiff::TiffDecoder},
+    DynamicImage, ImageDecoder, ImageFormat,
+};
+use log::{debug, warn};
+
+use super::{heic, jxl};
+
+/// Convert the image to sRGB if it embeds a color profile, and rotate or flip
+/// it as given by its EXIF orientation.
+pub fn apply(image: DynamicImage, path: &Path) -> DynamicImage {
+    let image = match icc_profile(path) {
+        Some(profile) => to_srgb(image, &profile, path),
+        None => image,
+    };
+    match orientation(path) {
+        Some(orientation) => orient(image, orientation),
+        None => image,
+    }
+}
+
+fn has_extension(path: &Path, names: &[&str]) -> bool {
+    path.extension()
+        .is_some_and(|ext| names.iter().any(|name| ext.eq_ignore_ascii_case(name)))
+}
+
+// Profiles are read separately from the pixels, which only requires the headers to be parsed
+fn icc_profile(path: &Path) -> Option<Vec<u8>> {
+    fn read<D: ImageDecoder<'static>>(decoder: image::ImageResult<D>) -> Option<Vec<u8>> {
+        decoder.ok()?.icc_profile()
+    }
+    fn open(path: &Path) -> Option<BufReader<File>> {
+        File::open(path).ok().map(BufReader::new)
+    }
+    if has_extension(path, &["heic", "heif", "avif"]) {
+        return heic::icc_profile(path);
+    }
+    if has_extension(path, &["jxl"]) {
+        return jxl::icc_profile(path);
+    }
+    match ImageFormat::from_path(path).ok()? {
+        ImageFormat::Jpeg => read(JpegDecoder::new(open(path)?)),
+        ImageFormat::Png => read(PngDecoder::new(open(path)?)),
+        ImageFormat::WebP => webp_icc_profile(path),
+        ImageFormat::Tiff => read(TiffDecoder::new(open(path)?)),
+        _ => None,
+    }
+}
+
+// WebP images not supported by the image crate are decoded by libwebp, so the
+// ICCP chunk of the extended format is looked up directly
+fn webp_icc_profile(path: &Path) -> Option<Vec<u8>> {
+    let data = std::fs::read(path).ok()?;
+    if data.get(..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    let mut offset = 12;
+    while let Some(header) = data.get(offset..offset + 8) {
+        let size = u32::from_le_bytes(header[4..].try_into().ok()?) as usize;
+        let chunk = data.get(offset + 8..offset + 8 + size)?;
+        if &header[..4] == b"ICCP" {
+            return Some(chunk.to_vec());
+        }
+        // Chunks are padded to an even size
+        offset += 8 + size + size % 2;
+    }
+    None
+}
+
+fn to_srgb(image: DynamicImage, profile: &[u8], path: &Path) -> DynamicImage {
+    let input = match qcms::Profile::new_from_slice(profile, false) {
+        Some(input) => input,
+        None => {
+            warn!("Ignoring the invalid color profile of {:?}", path);
+            return image;
+        }
+    };
+    let mut output = qcms::Profile::new_sRGB();
+    output.precache_output_transform();
+    let alpha = image.color().has_alpha();
+    let kind = if alpha {
+        qcms::DataType::RGBA8
+    } else {
+        qcms::DataType::RGB8
+    };
+    // Profiles of other color spaces, e.g. CMYK, are already handled by the decoder
+    let transform = match qcms::Transform::new(&input, &output, kind, qcms::Intent::Perceptual) {
+        Some(transform) => transform,
+        None => {
+            debug!(
+                "Ignoring the color profile of {:?} not describing RGB",
+                path
+            );
+            return image;
+        }
+    };
+    let start = std::time::Instant::now();
+    let deep = image.color().bytes_per_pixel() > image.color().channel_count();
+    let converted = match (deep, alpha) {
+        (true, true) => {
+            let mut pixels = image.to_rgba16();
+            apply_deep(&transform, &mut pixels);
+            DynamicImage::ImageRgba16(pixels)
+        }
+        (true, false) => {
+            let mut pixels = image.to_rgb16();
+            apply_deep(&transform, &mut pixels);
+            DynamicImage::ImageRgb16(pixels)
+        }
+        (false, true) => {
+            let mut pixels = image.to_rgba8();
+            transform.apply(&mut pixels);
+            DynamicImage::ImageRgba8(pixels)
+        }
+        (false, false) => {
+            let mut pixels = image.to_rgb8();
+            transform.apply(&mut pixels);
+            DynamicImage::ImageRgb8(pixels)
+        }
+    };
+    debug!(
+        "Converted the color profile of {:?} to sRGB in {}ms",
+        path,
+        start.elapsed().as_millis()
+    );
+    converted
+}
+
+// qcms only converts channels of 8 bits. The change it makes to the rounded
+// channels is added to the original ones, which keeps their finer steps.
+fn apply_deep(transform: &qcms::Transform, channels: &mut [u16]) {
+    let rounded: Vec<u8> = channels
+        .iter()
+        .map(|channel| ((*channel as u32 + 128) / 257) as u8)
+        .collect();
+    let mut converted = rounded.clone();
+    transform.apply(&mut converted);
+    for ((channel, before), after) in channels.iter_mut().zip(rounded).zip(converted) {
+        *channel = (*channel as i32 + (after as i32 - before as i32) * 257).clamp(0, 65535) as u16;
+    }
+}
+
+fn orientation(path: &Path) -> Option<u32> {
+    // libheif and jxl-oxide already apply the orientation stored in the image
+    if has_extension(path, &["heic", "heif", "avif", "jxl"]) {
+        return None;
+    }
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+// Values of the EXIF orientation, describing how the stored image has to be
+// transformed to be shown upright
+fn orient(image: DynamicImage, orientation: u32) -> DynamicImage {
+    debug!("Applying EXIF orientation {{ value: {} }}", orientation);
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
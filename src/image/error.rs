@@ -15,27 +15,17 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use image::error::DecodingError;
+use image::error::EncodingError;
 use image::error::LimitError;
 use image::error::UnsupportedError;
 use thiserror::Error;
 
-use cairo::BorrowError as CairoBorrowError;
-use cairo::Error as CairoError;
-
 #[derive(Error, Debug)]
 pub enum ImageError {
-    #[error("Could not create ImageSurface with Cairo: `{0}`")]
-    CouldNotCreateSurface(CairoError),
-    #[error("Could not create Context with Cairo: `{0}`")]
-    CouldNotCreateContext(CairoError),
-    #[error("Could not set source Surface to Context with Cairo: `{0}`")]
-    CouldNotSetSource(CairoError),
-    #[error("Could not write Result of Context with Cairo: `{0}`")]
-    CouldNotWriteResult(CairoError),
-    #[error("Could not get image data with Cairo: `{0}`")]
-    CouldNotGetData(CairoBorrowError),
     #[error("Could not decode image in: `{0}`")]
     CouldNotDecode(DecodingError),
+    #[error("Could not encode image: `{0}`")]
+    CouldNotEncode(EncodingError),
     #[error("Loading Image took more resources than allowed: `{0}`")]
     ResourceLimit(LimitError),
     #[error("Unsupported: `{0}`")]
@@ -44,6 +34,15 @@ pub enum ImageError {
     Io(std::io::Error),
     #[error("Image Buffer could not be interpreted: `{0}`")]
     BufferInvalid(fast_image_resize::ImageBufferError),
+    #[error("Image could not be cropped: `{0}`")]
+    CropInvalid(fast_image_resize::CropBoxError),
+    #[error("Support for `{0}` was not enabled at compile time")]
+    NotCompiled(String),
+    #[cfg(any(feature = "heic", feature = "avif"))]
+    #[error("Could not handle HEIF container: `{0}`")]
+    Heif(libheif_rs::HeifError),
+    #[error("Could not read SVG image: `{0}`")]
+    Svg(resvg::usvg::Error),
     #[error("Generic: `{0}`")]
     Generic(String),
 }
@@ -54,6 +53,12 @@ impl From<fast_image_resize::ImageBufferError> for ImageError {
     }
 }
 
+impl From<fast_image_resize::CropBoxError> for ImageError {
+    fn from(e: fast_image_resize::CropBoxError) -> Self {
+        Self::CropInvalid(e)
+    }
+}
+
 impl From<image::error::ImageError> for ImageError {
     fn from(org: image::error::ImageError) -> Self {
         match org {
@@ -64,11 +69,24 @@ impl From<image::error::ImageError> for ImageError {
             image::ImageError::Parameter(e) => {
                 ImageError::Generic(format!("Loading of image failed: {}", e))
             }
-            image::ImageError::Encoding(_) => unimplemented!(),
+            image::ImageError::Encoding(e) => ImageError::CouldNotEncode(e),
         }
     }
 }
 
+#[cfg(any(feature = "heic", feature = "avif"))]
+impl From<libheif_rs::HeifError> for ImageError {
+    fn from(e: libheif_rs::HeifError) -> Self {
+        Self::Heif(e)
+    }
+}
+
+impl From<resvg::usvg::Error> for ImageError {
+    fn from(e: resvg::usvg::Error) -> Self {
+        Self::Svg(e)
+    }
+}
+
 impl From<std::io::Error> for ImageError {
     fn from(e: std::io::Error) -> Self {
         Self::Io(e)
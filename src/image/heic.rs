@@ -0,0 +1,191 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// HEIC containers as used by the dynamic desktops of macOS hold all frames of
+// a wallpaper in a single file. The frames are decoded once and stored as
+// PNG in the cache directory, from there on they are treated like any other image.
+// Still HEIF and AVIF images are decoded by libheif as well.
+use std::path::{Path, PathBuf};
+
+use image::DynamicImage;
+
+use super::error::ImageError;
+
+pub struct Container {
+    /// Decoded frames in the order of the top level images of the container.
+    pub frames: Vec<PathBuf>,
+    /// XMP metadata of the primary image, which holds the description of the wallpaper.
+    pub xmp: Option<String>,
+}
+
+#[cfg(not(feature = "heic"))]
+pub fn extract<P: AsRef<Path>>(_path: P) -> Result<Container, ImageError> {
+    Err(ImageError::NotCompiled("heic".into()))
+}
+
+#[cfg(feature = "heic")]
+pub fn extract<P: AsRef<Path>>(path: P) -> Result<Container, ImageError> {
+    use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+    use image::ImageEncoder;
+    use libheif_rs::{HeifContext, LibHeif};
+    use log::debug;
+
+    let path = path.as_ref();
+    let cache = cache_directory(path)?;
+    let ctx = HeifContext::read_from_file(&path.to_string_lossy())?;
+    let xmp = ctx
+        .primary_image_handle()?
+        .all_metadata()
+        .into_iter()
+        .find(|meta| meta.content_type == "application/rdf+xml")
+        .map(|meta| String::from_utf8_lossy(&meta.raw_data).into_owned());
+
+    let lib_heif = LibHeif::new();
+    let mut frames = Vec::new();
+    for (idx, handle) in ctx.top_level_image_handles().iter().enumerate() {
+        let frame = cache.join(format!("frame-{idx}.png"));
+        if !frame.is_file() {
+            debug!("Decoding HEIC frame {{ path: {:?}, index: {} }}", path, idx);
+            let image = decode(&lib_heif, handle)?;
+            // Frames are renamed into place once complete, an interrupted run never leaves a truncated frame behind
+            let partial = cache.join(format!("frame-{idx}.png.{}", std::process::id()));
+            let file = std::fs::File::create(&partial)?;
+            PngEncoder::new_with_quality(file, CompressionType::Fast, FilterType::Sub)
+                .write_image(
+                    image.as_raw(),
+                    image.width(),
+                    image.height(),
+                    image::ColorType::Rgb8,
+                )?;
+            std::fs::rename(&partial, &frame)?;
+        }
+        frames.push(frame);
+    }
+    Ok(Container { frames, xmp })
+}
+
+#[cfg(not(feature = "heic"))]
+pub fn open_heif<P: AsRef<Path>>(_path: P) -> Result<DynamicImage, ImageError> {
+    Err(ImageError::NotCompiled("heic".into()))
+}
+
+/// Decode the primary image of a HEIF container.
+#[cfg(feature = "heic")]
+pub fn open_heif<P: AsRef<Path>>(path: P) -> Result<DynamicImage, ImageError> {
+    open_primary(path.as_ref())
+}
+
+#[cfg(not(feature = "avif"))]
+pub fn open_avif<P: AsRef<Path>>(_path: P) -> Result<DynamicImage, ImageError> {
+    Err(ImageError::NotCompiled("avif".into()))
+}
+
+/// Decode an AVIF image, which requires libheif to be built with an AV1 decoder.
+#[cfg(feature = "avif")]
+pub fn open_avif<P: AsRef<Path>>(path: P) -> Result<DynamicImage, ImageError> {
+    open_primary(path.as_ref())
+}
+
+#[cfg(not(any(feature = "heic", feature = "avif")))]
+pub fn icc_profile(_path: &Path) -> Option<Vec<u8>> {
+    None
+}
+
+/// The ICC profile of the primary image. Colors given as NCLX are taken as sRGB.
+#[cfg(any(feature = "heic", feature = "avif"))]
+pub fn icc_profile(path: &Path) -> Option<Vec<u8>> {
+    use libheif_rs::{color_profile_types, ColorProfile, HeifContext};
+
+    let ctx = HeifContext::read_from_file(&path.to_string_lossy()).ok()?;
+    let profile = ctx.primary_image_handle().ok()?.color_profile_raw()?;
+    [color_profile_types::PROF, color_profile_types::R_ICC]
+        .contains(&profile.profile_type())
+        .then_some(profile.data)
+}
+
+#[cfg(any(feature = "heic", feature = "avif"))]
+fn open_primary(path: &Path) -> Result<DynamicImage, ImageError> {
+    use libheif_rs::{HeifContext, LibHeif};
+    use log::debug;
+
+    debug!(
+        "Decoding primary image of HEIF container {{ path: {:?} }}",
+        path
+    );
+    let ctx = HeifContext::read_from_file(&path.to_string_lossy())?;
+    let image = decode(&LibHeif::new(), &ctx.primary_image_handle()?)?;
+    Ok(DynamicImage::ImageRgb8(image))
+}
+
+#[cfg(any(feature = "heic", feature = "avif"))]
+fn decode(
+    lib_heif: &libheif_rs::LibHeif,
+    handle: &libheif_rs::ImageHandle,
+) -> Result<image::RgbImage, ImageError> {
+    use libheif_rs::{ColorSpace, RgbChroma};
+
+    let image = lib_heif.decode(handle, ColorSpace::Rgb(RgbChroma::Rgb), None)?;
+    let plane = image
+        .planes()
+        .interleaved
+        .ok_or_else(|| ImageError::Generic("HEIF image has no interleaved plane".into()))?;
+    // Rows may be padded, only the visible part is copied
+    let row = plane.width as usize * 3;
+    let data: Vec<u8> = plane
+        .data
+        .chunks(plane.stride)
+        .take(plane.height as usize)
+        .flat_map(|line| line[..row].iter().copied())
+        .collect();
+    image::RgbImage::from_raw(plane.width, plane.height, data)
+        .ok_or_else(|| ImageError::Generic("HEIF image does not match its size".into()))
+}
+
+// Frames are stored per container and invalidated when the container changes
+#[cfg(feature = "heic")]
+fn cache_directory(path: &Path) -> Result<PathBuf, ImageError> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::time::UNIX_EPOCH;
+
+    let base: PathBuf = std::env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map_err(|_| ImageError::Generic("Neither XDG_CACHE_HOME nor HOME are set".into()))?;
+    let meta = std::fs::metadata(path)?;
+    let modified = meta
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    // The key has to stay the same across builds, so the hash is spelled out instead of relying on the std hasher
+    let key = [
+        path.canonicalize()?.as_os_str().as_bytes(),
+        &meta.len().to_le_bytes(),
+        &modified.as_secs().to_le_bytes(),
+        &modified.subsec_nanos().to_le_bytes(),
+    ]
+    .concat();
+    let dir = base.join("enkei").join(format!("{:016x}", fnv1a(&key)));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+// 64 bit FNV-1a
+#[cfg(feature = "heic")]
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -14,48 +14,178 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use super::{error::ImageError, scaling::Filter, scaling::Scaling, webp};
+use super::{
+    animation, animation::Timing, correction, error::ImageError, heic, jxl, scaling,
+    scaling::Filter, scaling::Frame, scaling::Placement, svg, webp,
+};
 use crate::outputs::ScaledMode;
-use image::{DynamicImage, ImageFormat};
-use log::debug;
+use image::{error::ImageFormatHint, DynamicImage, ImageFormat};
+use lazy_regex::regex_is_match;
+use log::{debug, warn};
+use resvg::usvg::Tree;
+
+/// Whether the file suffix belongs to an image format which can be shown directly.
+pub fn is_supported(path: &Path) -> bool {
+    regex_is_match!(
+        r"\.(?i)(png|jpg|jpeg|gif|webp|farbfeld|tif|tiff|bmp|ico|svg|svgz|avif|jxl|heic|heif){1}$",
+        &path.to_string_lossy()
+    )
+}
+
+fn is_extension(ext: &Path, names: &[&str]) -> bool {
+    names
+        .iter()
+        .any(|name| ext.as_os_str().eq_ignore_ascii_case(name))
+}
 
 pub struct Image {
-    inner: DynamicImage,
-    pub scaling: Scaling,
+    source: Source,
+    timing: Timing,
+    pub placement: Placement,
     pub filter: Filter,
 }
 
+enum Source {
+    // Animated images consist of multiple frames, still images of a single one
+    Raster(Vec<DynamicImage>),
+    // Rendered anew for each resolution
+    Vector(Box<Tree>),
+}
+
+/// All frames of an image scaled to the size of an output.
+pub struct ScaledImage {
+    pub frames: Vec<Frame>,
+    pub timing: Timing,
+}
+
+/// All frames of an image at its own size, placed on the outputs by the GPU.
+pub struct Original {
+    pub frames: Vec<Frame>,
+    pub width: u32,
+    pub height: u32,
+    pub timing: Timing,
+    /// The most common color, shown around the image with `dominant` backgrounds.
+    pub dominant: [u8; 3],
+}
+
 impl Image {
-    pub fn new(path: PathBuf, scaling: Scaling, filter: Filter) -> Result<Self, ImageError> {
+    /// Decode the image at `path`, still images are corrected by their EXIF
+    /// orientation and color profile unless `as_stored` is set.
+    pub fn new(
+        path: PathBuf,
+        placement: Placement,
+        filter: Filter,
+        as_stored: bool,
+    ) -> Result<Self, ImageError> {
+        if regex_is_match!(r"\.(?i)(svg|svgz)$", &path.to_string_lossy()) {
+            return Ok(Self {
+                source: Source::Vector(Box::new(svg::open(&path)?)),
+                timing: Timing::still(),
+                placement,
+                filter,
+            });
+        }
+        let animation = match ImageFormat::from_path(&path) {
+            Ok(ImageFormat::WebP) => webp::open_animation(&path)?,
+            _ => animation::open(&path)?,
+        };
+        if let Some((frames, timing)) = animation {
+            return Ok(Self {
+                source: Source::Raster(frames),
+                timing,
+                placement,
+                filter,
+            });
+        }
         let image = {
             let image = image::open(&path);
-            if let Err(image::ImageError::Unsupported(e)) = &image {
+            let image = if let Err(image::ImageError::Unsupported(e)) = &image {
                 match e.format_hint() {
-                    image::error::ImageFormatHint::Exact(ImageFormat::WebP) => webp::open(&path)?,
+                    ImageFormatHint::Exact(ImageFormat::WebP) => webp::open(&path)?,
+                    ImageFormatHint::Exact(ImageFormat::Avif) => heic::open_avif(&path)?,
+                    ImageFormatHint::PathExtension(ext) if is_extension(&ext, &["jxl"]) => {
+                        jxl::open(&path)?
+                    }
+                    ImageFormatHint::PathExtension(ext)
+                        if is_extension(&ext, &["heic", "heif"]) =>
+                    {
+                        heic::open_heif(&path)?
+                    }
                     _ => image?,
                 }
             } else {
                 image?
+            };
+            if as_stored {
+                image
+            } else {
+                correction::apply(image, &path)
             }
         };
         Ok(Self {
-            inner: image,
-            scaling,
+            source: Source::Raster(vec![image]),
+            timing: Timing::still(),
+            placement,
             filter,
         })
     }
 
-    pub fn process(&self, mode: &ScaledMode) -> Result<Vec<u8>, ImageError> {
+    /// The frames at their own size, `None` for vector images which are
+    /// rendered at the resolution of each output instead.
+    pub fn original(&self) -> Option<Original> {
+        match &self.source {
+            Source::Raster(frames) => {
+                let first = frames.first()?;
+                Some(Original {
+                    frames: frames.iter().map(Frame::of).collect(),
+                    width: first.width(),
+                    height: first.height(),
+                    timing: self.timing.clone(),
+                    dominant: scaling::dominant(first),
+                })
+            }
+            Source::Vector(_) => None,
+        }
+    }
+
+    pub fn process(&self, mode: &ScaledMode) -> Result<ScaledImage, ImageError> {
         let start = std::time::Instant::now();
-        let res = self.scaling.scale(&self.inner, mode, self.filter);
+        let mut timing = self.timing.clone();
+        let frames = match &self.source {
+            Source::Raster(frames) => {
+                // Each output holds its own scaled frames, which may exceed the limit on large outputs
+                let shown = if animation::exceeds_limit(
+                    frames.len(),
+                    mode.width as u32,
+                    mode.height as u32,
+                ) {
+                    warn!(
+                        "Showing only the first frame of the animation at {}x{}, its frames take more than {}MiB",
+                        mode.width,
+                        mode.height,
+                        animation::MEMORY_LIMIT / 1024 / 1024
+                    );
+                    timing = Timing::still();
+                    &frames[..1]
+                } else {
+                    &frames[..]
+                };
+                shown
+                    .iter()
+                    .map(|frame| self.placement.scale(frame, mode, self.filter))
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            Source::Vector(tree) => vec![svg::render(tree, mode, self.placement)?],
+        };
         debug!(
-            "Scaling of image to size {{ x: {}, y: {} }} took {}ms",
+            "Scaling of image to size {{ x: {}, y: {}, frames: {} }} took {}ms",
             mode.width,
             mode.height,
+            frames.len(),
             start.elapsed().as_millis()
         );
-        res
+        Ok(ScaledImage { frames, timing })
     }
 }
@@ -0,0 +1,94 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// JPEG XL images are decoded by `jxl-oxide`, animated images show their first frame
+use std::path::Path;
+
+use image::DynamicImage;
+
+use super::error::ImageError;
+
+#[cfg(not(feature = "jxl"))]
+pub fn open<P: AsRef<Path>>(_path: P) -> Result<DynamicImage, ImageError> {
+    Err(ImageError::NotCompiled("jxl".into()))
+}
+
+#[cfg(not(feature = "jxl"))]
+pub fn icc_profile(_path: &Path) -> Option<Vec<u8>> {
+    None
+}
+
+/// The profile of the color space the image is rendered in, `None` if that is sRGB.
+#[cfg(feature = "jxl")]
+pub fn icc_profile(path: &Path) -> Option<Vec<u8>> {
+    use jxl_oxide::JxlImage;
+
+    let image = JxlImage::builder().open(path).ok()?;
+    // Primaries and transfer function of sRGB
+    let srgb = image
+        .rendered_cicp()
+        .is_some_and(|cicp| cicp[..2] == [1, 13]);
+    (!srgb).then(|| image.rendered_icc())
+}
+
+#[cfg(feature = "jxl")]
+pub fn open<P: AsRef<Path>>(path: P) -> Result<DynamicImage, ImageError> {
+    use image::error::{DecodingError, ImageFormatHint, UnsupportedError, UnsupportedErrorKind};
+    use image::{GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+    use jxl_oxide::{JxlImage, PixelFormat};
+    use log::debug;
+
+    let format = || ImageFormatHint::Name("JPEG XL".into());
+    let decoding_error = |e| ImageError::CouldNotDecode(DecodingError::new(format(), e));
+
+    let image = JxlImage::builder()
+        .open(path.as_ref())
+        .map_err(decoding_error)?;
+    let render = image.render_frame(0).map_err(decoding_error)?;
+    let mut stream = render.stream();
+    let (width, height) = (stream.width(), stream.height());
+    debug!(
+        "Decoding JPEG XL image {{ path: {:?}, size: {}x{}, format: {:?} }}",
+        path.as_ref(),
+        width,
+        height,
+        image.pixel_format()
+    );
+    let mut data = vec![0u8; (width * height * stream.channels()) as usize];
+    stream.write_to_buffer(&mut data);
+
+    let size_mismatch = || ImageError::Generic("JPEG XL frame does not match its size".into());
+    match image.pixel_format() {
+        PixelFormat::Gray => GrayImage::from_raw(width, height, data)
+            .map(DynamicImage::ImageLuma8)
+            .ok_or_else(size_mismatch),
+        PixelFormat::Graya => GrayAlphaImage::from_raw(width, height, data)
+            .map(DynamicImage::ImageLumaA8)
+            .ok_or_else(size_mismatch),
+        PixelFormat::Rgb => RgbImage::from_raw(width, height, data)
+            .map(DynamicImage::ImageRgb8)
+            .ok_or_else(size_mismatch),
+        PixelFormat::Rgba => RgbaImage::from_raw(width, height, data)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(size_mismatch),
+        PixelFormat::Cmyk | PixelFormat::Cmyka => Err(ImageError::Unsupported(
+            UnsupportedError::from_format_and_kind(
+                format(),
+                UnsupportedErrorKind::GenericFeature("CMYK color".into()),
+            ),
+        )),
+    }
+}
@@ -14,10 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::num::NonZeroU32;
+use std::str::FromStr;
 
-use cairo::ImageSurface;
-use image::DynamicImage;
+use fast_image_resize::{CropBox, ImageView, ImageViewMut, PixelType, ResizeAlg, Resizer};
+use image::{
+    imageops::{self, FilterType},
+    DynamicImage, ImageBuffer, RgbImage, Rgba,
+};
 use log::debug;
 use serde::Deserialize;
 
@@ -25,6 +31,7 @@ use super::error::ImageError;
 
 use crate::outputs::ScaledMode;
 use clap::ArgEnum;
+use enkei::fill;
 
 #[derive(PartialEq, Debug, Clone, Copy, ArgEnum, Deserialize, Hash, Eq)]
 pub enum Filter {
@@ -38,192 +45,622 @@ pub enum Scaling {
     Fill,
     Fit,
     None,
+    Stretch,
+    Tile,
 }
 
-impl Scaling {
+/// The edge or corner of the output an image is placed at, when it does not
+/// cover the output exactly.
+#[derive(PartialEq, Debug, Clone, Copy, ArgEnum, Deserialize, Hash, Eq)]
+pub enum Alignment {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Alignment {
+    // Position of the image as the halves of the free space left and above of it
+    fn halves(&self) -> (i32, i32) {
+        match self {
+            Alignment::Center => (1, 1),
+            Alignment::Top => (1, 0),
+            Alignment::Bottom => (1, 2),
+            Alignment::Left => (0, 1),
+            Alignment::Right => (2, 1),
+            Alignment::TopLeft => (0, 0),
+            Alignment::TopRight => (2, 0),
+            Alignment::BottomLeft => (0, 2),
+            Alignment::BottomRight => (2, 2),
+        }
+    }
+
+    /// Offset of an image of the given size from the top left corner of the output.
+    /// Images larger than the output get negative offsets and are cropped.
+    pub fn offset(&self, width: u32, height: u32, geometry: &ScaledMode) -> (i32, i32) {
+        let (x, y) = self.halves();
+        (
+            (geometry.width - width as i32) * x / 2,
+            (geometry.height - height as i32) * y / 2,
+        )
+    }
+}
+
+/// What is shown where an image does not cover the whole output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Background {
+    Color([u8; 3]),
+    /// The most common color of the image.
+    Dominant,
+    /// A blurred and darkened copy of the image covering the output.
+    Blur,
+    /// The image mirrored at its edges.
+    Mirror,
+    /// Nothing, the backdrop shows through.
+    Transparent,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Color([0, 0, 0])
+    }
+}
+
+impl FromStr for Background {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        match spec.trim() {
+            "dominant" => Ok(Background::Dominant),
+            "blur" => Ok(Background::Blur),
+            "mirror" => Ok(Background::Mirror),
+            "transparent" => Ok(Background::Transparent),
+            color => fill::color(color)
+                .map(|color| {
+                    Background::Color(color.map(|channel| (channel * 255.0).round() as u8))
+                })
+                .map_err(|e| format!("{e}, or one of dominant, blur, mirror or transparent")),
+        }
+    }
+}
+
+// Blurred backgrounds are computed at a fraction of the output resolution,
+// placing images on the GPU approximates them with mipmaps
+pub const BLUR_REDUCTION: u32 = 16;
+const BLUR_SIGMA: f32 = 2.0;
+pub const BLUR_BRIGHTNESS: f32 = 0.6;
+
+/// The pixels of a background covering the whole output.
+pub enum Canvas {
+    Color([u8; 3]),
+    Image(RgbImage),
+}
+
+impl Canvas {
+    pub fn pixel(&self, column: u32, row: u32) -> [u8; 3] {
+        match self {
+            Canvas::Color(color) => *color,
+            Canvas::Image(image) => image.get_pixel(column, row).0,
+        }
+    }
+}
+
+impl Background {
+    /// What is drawn around the image, `None` if the image itself is extended
+    /// instead.
+    pub fn canvas(&self, image: &DynamicImage, geometry: &ScaledMode) -> Option<Canvas> {
+        let width = geometry.width.max(1) as u32;
+        let height = geometry.height.max(1) as u32;
+        match self {
+            Background::Color(color) => Some(Canvas::Color(*color)),
+            Background::Dominant => Some(Canvas::Color(dominant(image))),
+            Background::Blur => {
+                let small = image.resize_to_fill(
+                    (width / BLUR_REDUCTION).max(1),
+                    (height / BLUR_REDUCTION).max(1),
+                    FilterType::Triangle,
+                );
+                let mut blurred = imageops::blur(&small.to_rgb8(), BLUR_SIGMA);
+                for channel in blurred.iter_mut() {
+                    *channel = (*channel as f32 * BLUR_BRIGHTNESS) as u8;
+                }
+                Some(Canvas::Image(imageops::resize(
+                    &blurred,
+                    width,
+                    height,
+                    FilterType::Triangle,
+                )))
+            }
+            Background::Mirror | Background::Transparent => None,
+        }
+    }
+}
+
+/// The average of the most frequent colors, counted in coarse buckets.
+pub fn dominant(image: &DynamicImage) -> [u8; 3] {
+    let thumbnail = image.thumbnail(64, 64).to_rgba8();
+    let mut buckets: BTreeMap<[u8; 3], ([u64; 3], u64)> = BTreeMap::new();
+    for pixel in thumbnail.pixels().filter(|pixel| pixel[3] > 127) {
+        let (sum, count) = buckets
+            .entry([pixel[0] >> 4, pixel[1] >> 4, pixel[2] >> 4])
+            .or_default();
+        for (total, channel) in sum.iter_mut().zip(pixel.0) {
+            *total += channel as u64;
+        }
+        *count += 1;
+    }
+    buckets
+        .into_values()
+        .max_by_key(|(_, count)| *count)
+        .map(|(sum, count)| sum.map(|total| (total / count) as u8))
+        .unwrap_or_default()
+}
+
+/// The pixels of an image placed on an output, rows starting from the top.
+/// Colors of frames with alpha are premultiplied.
+#[derive(Debug, Clone)]
+pub enum Frame {
+    Rgb8(Vec<u8>),
+    /// Images which are transparent in parts.
+    Rgba8(Vec<u8>),
+    /// Images with more than 8 bits per channel keep their precision.
+    Rgba16(Vec<u16>),
+}
+
+impl Frame {
+    /// The pixels of the image at its own size.
+    pub fn of(buf: &DynamicImage) -> Self {
+        let color = buf.color();
+        if color.bytes_per_pixel() > color.channel_count() {
+            Frame::Rgba16(Placement::premultiplied_deep(buf).into_raw())
+        } else if color.has_alpha() {
+            Frame::Rgba8(Placement::premultiplied(buf))
+        } else {
+            Frame::Rgb8(buf.to_rgb8().into_raw())
+        }
+    }
+}
+
+type Rgba16Image = ImageBuffer<Rgba<u16>, Vec<u16>>;
+
+/// How images are fit to the output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub scaling: Scaling,
+    pub alignment: Alignment,
+    /// Factor by which images are enlarged before they are tiled.
+    pub tile_scale: f64,
+    pub background: Background,
+}
+
+// Placements are part of the key of cached images
+impl Eq for Placement {}
+
+impl std::hash::Hash for Placement {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.scaling.hash(state);
+        self.alignment.hash(state);
+        self.tile_scale.to_bits().hash(state);
+        self.background.hash(state);
+    }
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            scaling: Scaling::Fill,
+            alignment: Alignment::Center,
+            tile_scale: 1.0,
+            background: Background::default(),
+        }
+    }
+}
+
+impl Placement {
+    /// The size of an image on the output, before it is cropped.
+    pub fn size(&self, width: u32, height: u32, geometry: &ScaledMode) -> (u32, u32) {
+        let width_ratio = geometry.width as f64 / width as f64;
+        let height_ratio = geometry.height as f64 / height as f64;
+        let (x, y) = match self.scaling {
+            Scaling::Fill => {
+                let ratio = f64::max(width_ratio, height_ratio);
+                (ratio, ratio)
+            }
+            Scaling::Fit => {
+                let ratio = f64::min(width_ratio, height_ratio);
+                (ratio, ratio)
+            }
+            Scaling::None => (1.0, 1.0),
+            Scaling::Stretch => (width_ratio, height_ratio),
+            Scaling::Tile => (self.tile_scale, self.tile_scale),
+        };
+        (
+            ((width as f64 * x) as u32).max(1),
+            ((height as f64 * y) as u32).max(1),
+        )
+    }
+
+    /// Whether an image of the given size leaves no part of the output uncovered.
+    pub fn covers(&self, width: u32, height: u32, geometry: &ScaledMode) -> bool {
+        let (x, y) = self.alignment.offset(width, height, geometry);
+        self.scaling == Scaling::Tile
+            || (x <= 0
+                && y <= 0
+                && x + width as i32 >= geometry.width
+                && y + height as i32 >= geometry.height)
+    }
+
     pub fn scale(
         &self,
-        sur: &DynamicImage,
-        geometry: &ScaledMode,
-        filter: Filter,
-    ) -> Result<Vec<u8>, ImageError> {
-        match self {
-            Scaling::Fill => Scaling::fill(sur, geometry, filter),
-            Scaling::Fit => Scaling::fit(sur, geometry, filter),
-            Scaling::None => Scaling::none(sur, geometry),
-        }
-    }
-
-    fn none(buf: &DynamicImage, geometry: &ScaledMode) -> Result<Vec<u8>, ImageError> {
-        let pad_width = (geometry.width as f64 - buf.width() as f64) / 2.0;
-        let pad_height = (geometry.height as f64 - buf.height() as f64) / 2.0;
-
-        {
-            let image_data: Vec<u8> = buf
-                .to_rgba8()
-                .chunks_exact(4)
-                .flat_map(|arr| [arr[2], arr[1], arr[0], arr[3]])
-                .collect();
-            let width = buf.width();
-            let height = buf.height();
-            let stride = cairo::Format::Rgb24.stride_for_width(width).map_err(|_| {
-                ImageError::Generic(format!(
-                    "The stride could not be determined for width {}",
-                    width
-                ))
-            })?;
-            let surface = ImageSurface::create_for_data(
-                image_data,
-                cairo::Format::Rgb24,
-                width as i32,
-                height as i32,
-                stride,
-            )
-            .map_err(ImageError::CouldNotCreateSurface)?;
-
-            let target =
-                cairo::ImageSurface::create(cairo::Format::Rgb24, geometry.width, geometry.height)
-                    .map_err(ImageError::CouldNotCreateSurface)?;
-            let ctx = cairo::Context::new(&target).map_err(ImageError::CouldNotCreateContext)?;
-            ctx.set_source_surface(&surface, pad_width, pad_height)
-                .map_err(ImageError::CouldNotSetSource)?;
-            ctx.paint().map_err(ImageError::CouldNotWriteResult)?;
-            drop(ctx);
-
-            let data = target
-                .take_data()
-                .map_err(ImageError::CouldNotGetData)?
-                .to_vec();
-            Ok(data
-                .chunks_exact(4)
-                .flat_map(|arr| [arr[2], arr[1], arr[0]])
-                .collect())
-        }
-    }
-
-    fn fit(
         buf: &DynamicImage,
         geometry: &ScaledMode,
         filter: Filter,
-    ) -> Result<Vec<u8>, ImageError> {
-        Scaling::fill_or_fit(buf, geometry, filter, f64::min)
+    ) -> Result<Frame, ImageError> {
+        let color = buf.color();
+        if color.bytes_per_pixel() > color.channel_count() {
+            return self.scale_deep(buf, geometry, filter).map(Frame::Rgba16);
+        }
+        let (width, height) = self.size(buf.width(), buf.height(), geometry);
+        let (x, y) = self.alignment.offset(width, height, geometry);
+        let covered = self.covers(width, height, geometry);
+        let mirror = self.background == Background::Mirror && !covered;
+        // Transparent parts are kept, so that the backdrop shows through them
+        let alpha = color.has_alpha() || (self.background == Background::Transparent && !covered);
+        let (pixel_type, channels) = if alpha {
+            (PixelType::U8x4, 4)
+        } else {
+            (PixelType::U8x3, 3)
+        };
+        let columns = geometry.width.max(1) as usize;
+        let rows = geometry.height.max(1) as usize;
+        let stride = columns * channels;
+
+        // The image is scaled straight into the pixels of the texture
+        let mut pixels = vec![0u8; stride * rows];
+        let data = Placement::source(buf, alpha);
+        let mut source = ImageView::from_buffer(
+            nonzero(buf.width()),
+            nonzero(buf.height()),
+            &data,
+            pixel_type,
+        )?;
+        if self.scaling == Scaling::Tile || mirror {
+            // Extended images are scaled as a whole and repeated from there
+            let image_stride = width as usize * channels;
+            let mut image = vec![0u8; image_stride * height as usize];
+            let mut target =
+                ImageViewMut::from_buffer(nonzero(width), nonzero(height), &mut image, pixel_type)?;
+            Placement::resize(&source, &mut target, filter);
+            self.place(
+                &mut pixels,
+                &image,
+                columns,
+                (x, y),
+                (width, height),
+                mirror,
+            );
+        } else {
+            // Backgrounds are only computed when they are visible
+            let canvas = (!covered)
+                .then(|| self.background.canvas(buf, geometry))
+                .flatten();
+            if let Some(canvas) = canvas {
+                self.surround(
+                    &mut pixels,
+                    channels,
+                    columns,
+                    (x, y),
+                    (width, height),
+                    &canvas,
+                );
+            }
+
+            // Only the part of the image visible on the output is scaled. The
+            // crop is rounded to whole pixels of the image, which shifts enlarged
+            // images by less than half of one of their pixels
+            let left = x.max(0);
+            let top = y.max(0);
+            let right = (x + width as i32).min(columns as i32);
+            let bottom = (y + height as i32).min(rows as i32);
+            if left < right && top < bottom {
+                let project = |position: i32, offset: i32, size: u32, original: u32| {
+                    ((position - offset) as f64 * original as f64 / size as f64).round() as u32
+                };
+                let crop_left = project(left, x, width, buf.width()).min(buf.width() - 1);
+                let crop_top = project(top, y, height, buf.height()).min(buf.height() - 1);
+                let crop_right =
+                    project(right, x, width, buf.width()).clamp(crop_left + 1, buf.width());
+                let crop_bottom =
+                    project(bottom, y, height, buf.height()).clamp(crop_top + 1, buf.height());
+                source.set_crop_box(CropBox {
+                    left: crop_left,
+                    top: crop_top,
+                    width: nonzero(crop_right - crop_left),
+                    height: nonzero(crop_bottom - crop_top),
+                })?;
+                let visible_width = (right - left) as u32;
+                let visible_height = (bottom - top) as u32;
+                debug!(
+                    "Scaling visible part of image {{ crop: {}x{}+{}+{}, size: {}x{} }}",
+                    crop_right - crop_left,
+                    crop_bottom - crop_top,
+                    crop_left,
+                    crop_top,
+                    visible_width,
+                    visible_height
+                );
+                let band = &mut pixels[top as usize * stride..bottom as usize * stride];
+                if visible_width as usize == columns {
+                    let mut target = ImageViewMut::from_buffer(
+                        nonzero(visible_width),
+                        nonzero(visible_height),
+                        band,
+                        pixel_type,
+                    )?;
+                    Placement::resize(&source, &mut target, filter);
+                } else {
+                    // The rows of the output are only partly covered
+                    let image_stride = visible_width as usize * channels;
+                    let mut image = vec![0u8; image_stride * visible_height as usize];
+                    let mut target = ImageViewMut::from_buffer(
+                        nonzero(visible_width),
+                        nonzero(visible_height),
+                        &mut image,
+                        pixel_type,
+                    )?;
+                    Placement::resize(&source, &mut target, filter);
+                    for (target, row) in band
+                        .chunks_exact_mut(stride)
+                        .zip(image.chunks_exact(image_stride))
+                    {
+                        target[left as usize * channels..right as usize * channels]
+                            .copy_from_slice(row);
+                    }
+                }
+            }
+        }
+
+        if alpha {
+            // Sharpening filters may overshoot the alpha of a pixel
+            for pixel in pixels.chunks_exact_mut(4) {
+                let alpha = pixel[3];
+                for channel in &mut pixel[..3] {
+                    *channel = (*channel).min(alpha);
+                }
+            }
+            Ok(Frame::Rgba8(pixels))
+        } else {
+            Ok(Frame::Rgb8(pixels))
+        }
+    }
+
+    // Copy the scaled image to its position on the output, extending it if
+    // it is tiled or mirrored
+    fn place<T: Copy>(
+        &self,
+        pixels: &mut [T],
+        image: &[T],
+        columns: usize,
+        (x, y): (i32, i32),
+        (width, height): (u32, u32),
+        mirror: bool,
+    ) {
+        let channels = image.len() / (width as usize * height as usize);
+        let image_stride = width as usize * channels;
+        let extension = self.extension(mirror);
+        for (row, target) in pixels.chunks_exact_mut(columns * channels).enumerate() {
+            let Some(row) = extension(row as i32 - y, height) else {
+                continue;
+            };
+            let row = &image[row as usize * image_stride..][..image_stride];
+            for (column, pixel) in target.chunks_exact_mut(channels).enumerate() {
+                if let Some(column) = extension(column as i32 - x, width) {
+                    pixel.copy_from_slice(&row[column as usize * channels..][..channels]);
+                }
+            }
+        }
+    }
+
+    // Draw the canvas on all pixels of the output which the image leaves uncovered
+    fn surround<T: Channel>(
+        &self,
+        pixels: &mut [T],
+        channels: usize,
+        columns: usize,
+        (x, y): (i32, i32),
+        (width, height): (u32, u32),
+        canvas: &Canvas,
+    ) {
+        let horizontal = x..x + width as i32;
+        let vertical = y..y + height as i32;
+        for (row, target) in pixels.chunks_exact_mut(columns * channels).enumerate() {
+            let crossed = vertical.contains(&(row as i32));
+            for (column, pixel) in target.chunks_exact_mut(channels).enumerate() {
+                if crossed && horizontal.contains(&(column as i32)) {
+                    continue;
+                }
+                let color = canvas.pixel(column as u32, row as u32);
+                for (channel, value) in pixel.iter_mut().zip(color) {
+                    *channel = T::widen(value);
+                }
+                if channels == 4 {
+                    pixel[3] = T::MAX;
+                }
+            }
+        }
+    }
+
+    // Position within the image shown at a position of the output, if any
+    fn extension(&self, mirror: bool) -> impl Fn(i32, u32) -> Option<u32> {
+        let tile = self.scaling == Scaling::Tile;
+        move |position: i32, size: u32| {
+            let size = size as i32;
+            if tile {
+                Some(position.rem_euclid(size) as u32)
+            } else if mirror {
+                let reflected = position.rem_euclid(2 * size);
+                Some(reflected.min(2 * size - 1 - reflected) as u32)
+            } else {
+                (0..size).contains(&position).then_some(position as u32)
+            }
+        }
     }
 
-    fn fill(
+    // The resizer cannot crop images of four channels with 16 bits, images
+    // of a higher depth are scaled as a whole and then placed on the output
+    fn scale_deep(
+        &self,
         buf: &DynamicImage,
         geometry: &ScaledMode,
         filter: Filter,
-    ) -> Result<Vec<u8>, ImageError> {
-        Scaling::fill_or_fit(buf, geometry, filter, f64::max)
+    ) -> Result<Vec<u16>, ImageError> {
+        let (width, height) = self.size(buf.width(), buf.height(), geometry);
+        let image = Placement::resize_deep(buf, width, height, filter)?;
+        let (x, y) = self.alignment.offset(width, height, geometry);
+        let covered = self.covers(width, height, geometry);
+        let mirror = self.background == Background::Mirror && !covered;
+        let columns = geometry.width.max(1) as usize;
+        let rows = geometry.height.max(1) as usize;
+
+        let mut pixels = vec![0u16; columns * rows * 4];
+        if self.scaling != Scaling::Tile && !mirror {
+            let canvas = (!covered)
+                .then(|| self.background.canvas(buf, geometry))
+                .flatten();
+            if let Some(canvas) = canvas {
+                self.surround(&mut pixels, 4, columns, (x, y), (width, height), &canvas);
+            }
+        }
+        self.place(
+            &mut pixels,
+            image.as_raw(),
+            columns,
+            (x, y),
+            (width, height),
+            mirror,
+        );
+        Ok(pixels)
     }
 
-    fn fill_or_fit<F: Fn(f64, f64) -> f64>(
+    fn resize_deep(
         buf: &DynamicImage,
-        geometry: &ScaledMode,
+        width: u32,
+        height: u32,
         filter: Filter,
-        comp: F,
-    ) -> Result<Vec<u8>, ImageError> {
-        // 1. Crop the image if necessary
-        // 2. Scale the image to the proper size
-
-        let height_ratio = geometry.height as f64 / buf.height() as f64;
-        let width_ratio = geometry.width as f64 / buf.width() as f64;
-        let max_ratio = comp(height_ratio, width_ratio);
-
-        // Get cropping edges (aspect)
-        // This needs to take into accordance the now larger scaled
-        let crop_height = ((buf.height() as f64 * max_ratio) as i32)
-            .checked_sub(geometry.height)
-            .map(|elem| (elem / 2) as f64)
-            .unwrap_or(0.0);
-        let crop_width = ((buf.width() as f64 * max_ratio) as i32)
-            .checked_sub(geometry.width)
-            .map(|elem| (elem / 2) as f64)
-            .unwrap_or(0.0);
-
-        /*
-         * SIMD Resize
-         */
-        let width = (buf.width() as f64 * max_ratio) as u32;
-        let height = (buf.height() as f64 * max_ratio) as u32;
-
-        let image_data: Vec<u8>;
-        if width != buf.width() && buf.height() != height {
-            debug!("Using SIMD image resizing");
-            let fallback = NonZeroU32::new(1).expect("Cannot fail");
-            let orig_image = fast_image_resize::Image::from_vec_u8(
-                NonZeroU32::new(buf.width() as u32).unwrap_or(fallback),
-                NonZeroU32::new(buf.height() as u32).unwrap_or(fallback),
-                buf.to_rgba8().into_raw(),
-                fast_image_resize::PixelType::U8x4,
-            )?;
-
-            let mut scaled_image = fast_image_resize::Image::new(
-                NonZeroU32::new(width).unwrap_or(fallback),
-                NonZeroU32::new(height).unwrap_or(fallback),
-                fast_image_resize::PixelType::U8x4,
-            );
-            let mut scaled_view = scaled_image.view_mut();
-            let mut resizer = fast_image_resize::Resizer::new(
-                fast_image_resize::ResizeAlg::Convolution(filter.into()),
-            );
-            // This function only fails if we use different kinds of PixelTypes
-            resizer
-                .resize(&orig_image.view(), &mut scaled_view)
-                .expect("Cannot fail");
-
-            image_data = scaled_image
-                // Turn around because of endianness of cairo...
-                .buffer()
-                .chunks_exact(4)
-                .flat_map(|arr| [arr[2], arr[1], arr[0], arr[3]])
-                .collect();
-        } else {
+    ) -> Result<Rgba16Image, ImageError> {
+        if buf.color().has_alpha() {
+            let image = Placement::premultiplied_deep(buf);
+            if width == image.width() && height == image.height() {
+                debug!("No scaling required for image");
+                return Ok(image);
+            }
+            // The SIMD resizer knows no four channels of 16 bits
+            debug!("Resizing image with alpha channel of 16 bits");
+            let mut image = imageops::resize(&image, width, height, filter.into());
+            // Sharpening filters may overshoot the alpha of a pixel
+            for pixel in image.pixels_mut() {
+                let alpha = pixel[3];
+                for channel in &mut pixel.0[..3] {
+                    *channel = (*channel).min(alpha);
+                }
+            }
+            return Ok(image);
+        }
+        if width == buf.width() && height == buf.height() {
             debug!("No scaling required for image");
-            // Flippity Flop, it's time to stop
-            image_data = buf
-                .to_rgba8()
+            return Ok(buf.to_rgba16());
+        }
+        debug!("Using SIMD image resizing of 16 bit channels");
+        let orig_image = fast_image_resize::Image::from_vec_u8(
+            nonzero(buf.width()),
+            nonzero(buf.height()),
+            buf.to_rgb16()
                 .into_raw()
-                .chunks_exact(4)
-                .flat_map(|arr| [arr[2], arr[1], arr[0], arr[3]])
-                .collect();
-        }
-
-        let stride = cairo::Format::ARgb32.stride_for_width(width).map_err(|_| {
-            ImageError::Generic(format!(
-                "The stride could not be determined for width {}",
-                width
-            ))
-        })?;
-        let surface = ImageSurface::create_for_data(
-            image_data,
-            cairo::Format::ARgb32,
-            width as i32,
-            height as i32,
-            stride,
-        )
-        .map_err(ImageError::CouldNotCreateSurface)?;
+                .into_iter()
+                .flat_map(u16::to_ne_bytes)
+                .collect(),
+            fast_image_resize::PixelType::U16x3,
+        )?;
+        let mut scaled_image = fast_image_resize::Image::new(
+            nonzero(width),
+            nonzero(height),
+            fast_image_resize::PixelType::U16x3,
+        );
+        let mut resizer = fast_image_resize::Resizer::new(
+            fast_image_resize::ResizeAlg::Convolution(filter.into()),
+        );
+        // This function only fails if we use different kinds of PixelTypes
+        resizer
+            .resize(&orig_image.view(), &mut scaled_image.view_mut())
+            .expect("Cannot fail");
+        let pixels = scaled_image
+            .buffer()
+            .chunks_exact(6)
+            .flat_map(|pixel| {
+                let channel = |i: usize| u16::from_ne_bytes([pixel[i], pixel[i + 1]]);
+                [channel(0), channel(2), channel(4), u16::MAX]
+            })
+            .collect();
+        Ok(Rgba16Image::from_raw(width, height, pixels).expect("Cannot fail"))
+    }
+
+    // Scale the crop box of the source to the size of the target
+    fn resize(source: &ImageView, target: &mut ImageViewMut, filter: Filter) {
+        let crop = source.crop_box();
+        let algorithm = if crop.width == target.width() && crop.height == target.height() {
+            debug!("No scaling required for image");
+            ResizeAlg::Nearest
+        } else {
+            debug!("Using SIMD image resizing");
+            ResizeAlg::Convolution(filter.into())
+        };
+        // This function only fails if we use different kinds of PixelTypes
+        Resizer::new(algorithm)
+            .resize(source, target)
+            .expect("Cannot fail");
+    }
+
+    // The pixels of the image in the layout of the texture, borrowed if
+    // they are already
+    fn source(buf: &DynamicImage, alpha: bool) -> Cow<'_, [u8]> {
+        match buf {
+            DynamicImage::ImageRgb8(image) if !alpha => Cow::Borrowed(image.as_raw()),
+            _ if alpha => Cow::Owned(Placement::premultiplied(buf)),
+            _ => Cow::Owned(buf.to_rgb8().into_raw()),
+        }
+    }
 
-        // Create context and scale and crop to fit
-        {
-            let target: ImageSurface =
-                cairo::ImageSurface::create(cairo::Format::Rgb24, geometry.width, geometry.height)
-                    .map_err(ImageError::CouldNotCreateSurface)?;
-            let ctx = cairo::Context::new(&target).map_err(ImageError::CouldNotCreateContext)?;
-            ctx.set_source_surface(&surface, -crop_width, -crop_height)
-                .map_err(ImageError::CouldNotSetSource)?;
-            ctx.paint().map_err(ImageError::CouldNotWriteResult)?;
-            drop(ctx);
+    fn premultiplied_deep(buf: &DynamicImage) -> Rgba16Image {
+        let mut image = buf.to_rgba16();
+        if buf.color().has_alpha() {
+            for pixel in image.pixels_mut() {
+                let alpha = pixel[3] as u32;
+                for channel in &mut pixel.0[..3] {
+                    *channel = ((*channel as u32 * alpha + 32767) / 65535) as u16;
+                }
+            }
+        }
+        image
+    }
 
-            let data = target
-                .take_data()
-                .map_err(ImageError::CouldNotGetData)?
-                .to_vec();
-            Ok(data
-                .chunks_exact(4)
-                .flat_map(|arr| [arr[2], arr[1], arr[0]])
-                .collect())
+    // Colors are premultiplied by their alpha, which keeps transparent pixels
+    // from bleeding into their neighbours when resizing
+    fn premultiplied(buf: &DynamicImage) -> Vec<u8> {
+        let mut data = buf.to_rgba8().into_raw();
+        if buf.color().has_alpha() {
+            for pixel in data.chunks_exact_mut(4) {
+                let alpha = pixel[3] as u16;
+                for channel in &mut pixel[..3] {
+                    *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+                }
+            }
         }
+        data
     }
 }
 
@@ -236,3 +673,41 @@ impl From<Filter> for fast_image_resize::FilterType {
         }
     }
 }
+
+impl From<Filter> for FilterType {
+    fn from(filter: Filter) -> Self {
+        match filter {
+            Filter::Fast => FilterType::Triangle,
+            Filter::Good => FilterType::CatmullRom,
+            Filter::Best => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// Channels of the pixels placed on the output.
+trait Channel: Copy {
+    const MAX: Self;
+
+    /// The same intensity as a channel of 8 bits.
+    fn widen(value: u8) -> Self;
+}
+
+impl Channel for u8 {
+    const MAX: Self = u8::MAX;
+
+    fn widen(value: u8) -> Self {
+        value
+    }
+}
+
+impl Channel for u16 {
+    const MAX: Self = u16::MAX;
+
+    fn widen(value: u8) -> Self {
+        value as u16 * 257
+    }
+}
+
+fn nonzero(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap_or(NonZeroU32::MIN)
+}
@@ -0,0 +1,158 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Vector images are rendered for each output separately, so they stay sharp
+// on outputs of any resolution
+use std::{
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use image::{DynamicImage, RgbaImage};
+use log::debug;
+use resvg::{
+    tiny_skia::{BlendMode, FilterQuality, Paint, Pattern, Pixmap, Rect, SpreadMode, Transform},
+    usvg::{fontdb::Database, Options, Tree},
+};
+
+use super::{
+    error::ImageError,
+    scaling::{Background, Frame, Placement, Scaling},
+};
+use crate::outputs::ScaledMode;
+
+// Loading the system fonts is slow, they are shared between all images
+fn fonts() -> Arc<Database> {
+    static FONTS: OnceLock<Arc<Database>> = OnceLock::new();
+    Arc::clone(FONTS.get_or_init(|| {
+        let mut fonts = Database::new();
+        fonts.load_system_fonts();
+        debug!("Loaded fonts for SVG images {{ faces: {} }}", fonts.len());
+        Arc::new(fonts)
+    }))
+}
+
+/// Parse an SVG image, references to other files are resolved relative to it.
+pub fn open(path: &Path) -> Result<Tree, ImageError> {
+    let data = std::fs::read(path)?;
+    let options = Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        fontdb: fonts(),
+        ..Options::default()
+    };
+    Ok(Tree::from_data(&data, &options)?)
+}
+
+// Longest edge in pixels of the rendering backgrounds are derived from
+const PREVIEW_SIZE: f32 = 256.0;
+
+fn pixmap(width: u32, height: u32) -> Result<Pixmap, ImageError> {
+    Pixmap::new(width, height).ok_or_else(|| {
+        ImageError::Generic(format!(
+            "Cannot render SVG image with size {}x{}",
+            width, height
+        ))
+    })
+}
+
+// A small rendering of the image, enough to derive backgrounds from
+fn preview(tree: &Tree) -> Result<DynamicImage, ImageError> {
+    let size = tree.size();
+    let ratio = PREVIEW_SIZE / size.width().max(size.height());
+    let width = (size.width() * ratio).ceil() as u32;
+    let height = (size.height() * ratio).ceil() as u32;
+    let mut preview = pixmap(width, height)?;
+    resvg::render(
+        tree,
+        Transform::from_scale(ratio, ratio),
+        &mut preview.as_mut(),
+    );
+    RgbaImage::from_raw(width, height, preview.take())
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| ImageError::Generic("Cannot preview SVG image".into()))
+}
+
+/// Render the image at the resolution of the output. The image is scaled
+/// before rasterizing, uncovered areas show the background.
+pub fn render(tree: &Tree, mode: &ScaledMode, placement: Placement) -> Result<Frame, ImageError> {
+    let size = tree.size();
+    let (width, height) = placement.size(
+        size.width().ceil() as u32,
+        size.height().ceil() as u32,
+        mode,
+    );
+    let (x, y) = placement.alignment.offset(width, height, mode);
+    let scale = Transform::from_scale(width as f32 / size.width(), height as f32 / size.height());
+
+    let mut pixmap = pixmap(mode.width as u32, mode.height as u32)?;
+    let covered = placement.covers(width, height, mode);
+    if !covered {
+        if let Some(canvas) = placement.background.canvas(&preview(tree)?, mode) {
+            let columns = pixmap.width();
+            for (index, target) in pixmap.data_mut().chunks_exact_mut(4).enumerate() {
+                let [red, green, blue] =
+                    canvas.pixel(index as u32 % columns, index as u32 / columns);
+                target.copy_from_slice(&[red, green, blue, 255]);
+            }
+            // Backgrounds only surround the image
+            let clear = Paint {
+                blend_mode: BlendMode::Clear,
+                ..Paint::default()
+            };
+            if let Some(area) = Rect::from_xywh(x as f32, y as f32, width as f32, height as f32) {
+                pixmap.fill_rect(area, &clear, Transform::identity(), None);
+            }
+        }
+    }
+    let spread = match placement.scaling {
+        Scaling::Tile => Some(SpreadMode::Repeat),
+        _ if placement.background == Background::Mirror && !covered => Some(SpreadMode::Reflect),
+        _ => None,
+    };
+    if let Some(spread) = spread {
+        let mut tile = self::pixmap(width, height)?;
+        resvg::render(tree, scale, &mut tile.as_mut());
+        let pattern = Pattern::new(
+            tile.as_ref(),
+            spread,
+            FilterQuality::Nearest,
+            1.0,
+            Transform::from_translate(x as f32, y as f32),
+        );
+        let paint = Paint {
+            shader: pattern,
+            ..Paint::default()
+        };
+        let area = Rect::from_xywh(0.0, 0.0, mode.width as f32, mode.height as f32)
+            .expect("The output has a positive size");
+        pixmap.fill_rect(area, &paint, Transform::identity(), None);
+    } else {
+        let transform = scale.post_translate(x as f32, y as f32);
+        resvg::render(tree, transform, &mut pixmap.as_mut());
+    }
+    // Colors are premultiplied like those of all frames with alpha
+    if pixmap.pixels().iter().all(|pixel| pixel.alpha() == u8::MAX) {
+        Ok(Frame::Rgb8(
+            pixmap
+                .data()
+                .chunks_exact(4)
+                .flat_map(|arr| [arr[0], arr[1], arr[2]])
+                .collect(),
+        ))
+    } else {
+        Ok(Frame::Rgba8(pixmap.take()))
+    }
+}
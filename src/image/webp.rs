@@ -15,11 +15,19 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 // Add extra decoder function to webp images as `image` does not support most common formats
-use image::DynamicImage;
+use image::{
+    error::{DecodingError, ImageFormatHint},
+    DynamicImage, ImageFormat, RgbaImage,
+};
+use libwebp_sys::{
+    WebPAnimDecoderDelete, WebPAnimDecoderGetInfo, WebPAnimDecoderGetNext,
+    WebPAnimDecoderHasMoreFrames, WebPAnimDecoderNew, WebPAnimDecoderOptions,
+    WebPAnimDecoderOptionsInit, WebPAnimInfo, WebPData, WEBP_CSP_MODE,
+};
 use log::debug;
 use std::{io::Read, path::Path};
 
-use super::error::ImageError;
+use super::{animation, animation::Timing, error::ImageError};
 
 pub fn open<P: AsRef<Path>>(path: P) -> Result<DynamicImage, ImageError> {
     debug!("Fallback to separate webp decoder. Format was not supported.");
@@ -27,5 +35,82 @@ pub fn open<P: AsRef<Path>>(path: P) -> Result<DynamicImage, ImageError> {
     let mut data = Vec::new();
     file.read_to_end(&mut data)?;
     let decoder = webp::Decoder::new(&data);
-    Ok(decoder.decode().unwrap().to_image())
+    decoder
+        .decode()
+        .map(|image| image.to_image())
+        .ok_or_else(|| {
+            ImageError::CouldNotDecode(DecodingError::new(
+                ImageFormatHint::Exact(ImageFormat::WebP),
+                "Invalid or unsupported WebP data",
+            ))
+        })
+}
+
+/// Decode all frames of an animated WebP image. Returns `None` for still
+/// images and data not understood by the animation decoder, these are
+/// decoded as still images.
+pub fn open_animation<P: AsRef<Path>>(
+    path: P,
+) -> Result<Option<(Vec<DynamicImage>, Timing)>, ImageError> {
+    let data = std::fs::read(path.as_ref())?;
+    let webp_data = WebPData {
+        bytes: data.as_ptr(),
+        size: data.len(),
+    };
+    // SAFETY: `data` outlives the decoder, which is deleted before returning.
+    // Frame buffers are copied before the next call to the decoder.
+    unsafe {
+        let mut options = std::mem::MaybeUninit::<WebPAnimDecoderOptions>::uninit();
+        if WebPAnimDecoderOptionsInit(options.as_mut_ptr()) == 0 {
+            return Err(ImageError::Generic(
+                "Version mismatch of the WebP animation decoder".into(),
+            ));
+        }
+        let mut options = options.assume_init();
+        options.color_mode = WEBP_CSP_MODE::MODE_RGBA;
+        let decoder = WebPAnimDecoderNew(&webp_data, &options);
+        if decoder.is_null() {
+            return Ok(None);
+        }
+        let mut info = WebPAnimInfo::default();
+        if WebPAnimDecoderGetInfo(decoder, &mut info) == 0 || info.frame_count < 2 {
+            WebPAnimDecoderDelete(decoder);
+            return Ok(None);
+        }
+        debug!(
+            "Decoding animated WebP {{ frames: {}, size: {}x{}, loops: {} }}",
+            info.frame_count, info.canvas_width, info.canvas_height, info.loop_count
+        );
+        let mut frames = Vec::with_capacity(info.frame_count as usize);
+        let mut delays = Vec::with_capacity(info.frame_count as usize);
+        let mut last_timestamp = 0;
+        let len = info.canvas_width as usize * info.canvas_height as usize * 4;
+        while WebPAnimDecoderHasMoreFrames(decoder) != 0 {
+            let mut buf = std::ptr::null_mut();
+            let mut timestamp = 0;
+            if WebPAnimDecoderGetNext(decoder, &mut buf, &mut timestamp) == 0 {
+                WebPAnimDecoderDelete(decoder);
+                return Err(ImageError::Generic(format!(
+                    "Could not decode frame {} of WebP image {:?}",
+                    frames.len(),
+                    path.as_ref()
+                )));
+            }
+            if animation::exceeds_limit(frames.len() + 1, info.canvas_width, info.canvas_height) {
+                WebPAnimDecoderDelete(decoder);
+                return Ok(Some(animation::first_frame(path.as_ref(), frames)));
+            }
+            let canvas = std::slice::from_raw_parts(buf, len).to_vec();
+            let frame = RgbaImage::from_raw(info.canvas_width, info.canvas_height, canvas)
+                .expect("Buffer matches the canvas size");
+            frames.push(DynamicImage::ImageRgba8(frame));
+            // Timestamps mark the end of each frame in milliseconds
+            delays.push((timestamp - last_timestamp) as f64 / 1000.0);
+            last_timestamp = timestamp;
+        }
+        WebPAnimDecoderDelete(decoder);
+        // A loop count of zero repeats the animation forever
+        let loops = (info.loop_count > 0).then_some(info.loop_count);
+        Ok(Some((frames, Timing::new(delays).with_loops(loops))))
+    }
 }
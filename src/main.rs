@@ -21,17 +21,21 @@ use serde::Deserialize;
 use wayland_client::{protocol::wl_registry::WlRegistry, Attached, GlobalEvent, Main};
 use wayland_client::{ConnectError, GlobalError};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::mpsc::channel;
 use std::sync::RwLock;
 
 use wayland_client::{protocol::wl_output, Display, GlobalManager};
 
+use chrono::NaiveTime;
 use clap::ArgEnum;
+use enkei::cli::{parse_duration, parse_offset, parse_time_of_day};
 use lazy_regex::regex_is_match;
 
+mod check;
 mod error_report;
+mod generate;
 mod image;
 mod messages;
 mod metadata;
@@ -44,7 +48,12 @@ mod watchdog;
 mod worker;
 
 use crate::image::error::ImageError;
-use crate::metadata::MetadataReader;
+use crate::metadata::{
+    Appearance, Clock, Location, MetadataReader, Order, Preference, Settings, SlideshowSettings,
+    Source,
+};
+use crate::util::{Seek, VirtualClock};
+use enkei::fill::Fill;
 
 use thiserror::Error;
 
@@ -54,13 +63,16 @@ use egl::API as egl;
 
 use outputs::{handle_output_events, Output};
 
-use crate::image::scaling::{Filter, Scaling};
+use crate::image::scaling::{Alignment, Background, Filter, Placement, Scaling};
+use crate::opengl::dither::Dither;
 use khronos_egl::Error as EglError;
 
 #[derive(Error, Debug)]
 pub enum ApplicationError {
     #[error("Image Processing failed: `{0}`")]
     ErrorWhileImageProcessing(ImageError),
+    #[error("Could not load image `{0:?}`{1}: `{2}`")]
+    UnreadableImage(PathBuf, Box<str>, ImageError),
     #[error("Reading of metadata failed: `{0}`")]
     MetadataError(MetadataError),
     #[error("Could not determine data type, try to specify via --mode. Or check given file")]
@@ -79,7 +91,7 @@ pub enum ApplicationError {
     WaylandObject(GlobalError),
     #[error("Output Data was not ready, field value 'None' encountered")]
     OutputDataNotReady,
-    #[error("The path `{0}` is not a file or does not exist")]
+    #[error("The path `{0}` is not a file or directory or does not exist")]
     NotAFile(PathBuf),
 }
 
@@ -95,6 +107,14 @@ impl ApplicationError {
     fn egl_error(e: crate::EglError, line: u32, file: &str) -> ApplicationError {
         ApplicationError::EGL(format!("{file}:{line}"), e)
     }
+
+    // Images given in descriptions name the entry referencing them
+    fn unreadable(path: &Path, entry: Option<&str>, e: ImageError) -> ApplicationError {
+        let entry = entry
+            .map(|entry| format!(" given in {entry}"))
+            .unwrap_or_default();
+        ApplicationError::UnreadableImage(path.to_path_buf(), entry.into(), e)
+    }
 }
 
 impl From<image::error::ImageError> for ApplicationError {
@@ -114,10 +134,31 @@ const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
 const DESC: &str = env!("CARGO_PKG_DESCRIPTION");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const FILE_HELP: &str = "The path to the wallpaper to be shown. The mode, static or dynamic, gets determined automatically by default, based on the file suffix.";
+const FILE_HELP: &str = "The path to the wallpaper to be shown. The mode, static or dynamic, gets determined automatically by default, based on the file suffix. Directories are shown as a slideshow of the contained images.";
+const COLOR_HELP: &str = "Show a solid color or a gradient instead of a file, drawn at the resolution of each output. Either a color like `#1e1e2e`, or a CSS like gradient such as `linear-gradient(45deg, #1e1e2e, #89b4fa 80%)` or `radial-gradient(#89b4fa, #1e1e2e)` with up to 16 colors.";
 const MODE_HELP: &str = "The display mode, static or dynamic, to be used for the given wallpaper. Normally this gets detected automatically based on the file suffix. If this is not possible set it explicitly here.";
-const SCALE_HELP: &str = "The scaling mode, which should be used to fit the image to the screen. Fit will try to fit the whole image to the screen, while Fill will try to fill the screen completely upscaling and cropping the image if necessary.";
-const FILTER_HELP: &str = "The filter method which should be applied when a wallpaper is scaled. Variants correspond to cairo filters.";
+const SCALE_HELP: &str = "The scaling mode, which should be used to fit the image to the screen. Fit will try to fit the whole image to the screen, while Fill will try to fill the screen completely upscaling and cropping the image if necessary. Stretch fills the screen ignoring the aspect ratio, Tile repeats the image at its own size enlarged by --tile-scale, and None shows it at its own size.";
+const ALIGN_HELP: &str = "The edge or corner of the screen the image is placed at. With Fill this chooses which part of the image stays visible when it is cropped, with Fit and None where the image is shown, and with Tile where the first tile starts.";
+const BACKGROUND_HELP: &str = "What is shown where an image does not cover the whole screen, like with --scale fit. Either a color like `#1e1e2e`, `dominant` for the most common color of the image, `blur` for a blurred and darkened copy of the image filling the screen, `mirror` to extend the image by mirroring it at its edges, or `transparent` to show the --backdrop.";
+const BACKDROP_HELP: &str = "What is shown below transparent parts of the wallpaper, like images with an alpha channel or --background transparent. Either a color like `#1e1e2e`, a gradient as given to --color, or the path of an image which covers the screen as with --scale fill. Useful to show a logo with transparent surroundings on top of another image. Black by default.";
+const AS_STORED_HELP: &str = "Show images exactly as stored in their files. By default photos are rotated upright as given by their EXIF orientation, and converted from their embedded color profile to sRGB. Useful for images which have already been corrected.";
+const DITHER_HELP: &str = "Add a faint pattern below the visible color steps to the drawn wallpaper, which breaks up the bands of smooth gradients and slow crossfades on outputs with 8 bits per channel. Ordered uses a regular 8x8 pattern, Noise a blue noise pattern which is less noticeable. Images with 16 bits per channel, colors and gradients keep this precision until they are drawn.";
+const GPU_SCALING_HELP: &str = "Upload each image once at its own size and let the GPU scale, crop and extend it while drawing, instead of placing it on each output beforehand. Changing the scaling or filter and adding outputs is then nearly instant, and all outputs share one texture per image. Blurred backgrounds are approximated. Vector images and images larger than the GPU supports are still placed beforehand.";
+const TILE_SCALE_HELP: &str = "The factor by which images are enlarged before they are tiled with `--scale tile`, e.g. 2 on outputs with a scale factor of 2 to show each pixel of the image as 2x2 pixels.";
+const FILTER_HELP: &str = "The filter method which should be applied when a wallpaper is scaled. Fast uses a bilinear, Good a Catmull-Rom and Best a Lanczos filter.";
+const APPEARANCE_HELP: &str = "Which variant of wallpapers with a light and a dark variant is shown. System follows the color scheme of the desktop as reported by the XDG desktop portal, or by gsettings without one. Schedule shows the dark variant between --dark-from and --light-from.";
+const SCHEDULE_HELP: &str = "The local time of day, formatted as HH:MM, at which the schedule switches to the respective variant.";
+const CLOCK_HELP: &str = "How the time since the start of a dynamic wallpaper is counted. Wall follows the local clock, so a cycle of 24 hours always starts at the same time of day, also when daylight saving time or the time zone changes. Absolute counts the time which actually passed since the start, like Gnome does. Schedules and macOS dynamic desktops always follow the wall clock.";
+const INTERVAL_HELP: &str = "The number of seconds each image of a slideshow is shown, including the crossfade into the next image.";
+const ORDER_HELP: &str = "The order in which the images of a slideshow are shown. Name sorts by path, Mtime shows the oldest modified image first, and Shuffle shows all images in random order before any is repeated.";
+const CHECK_HELP: &str = "Read the given wallpaper and report problems, like missing or undecodable images, non-positive durations, and transitions not starting from the preceding image. The resolved timeline is printed with local times. Exits with a non-zero code if any problem has been found.";
+const TIME_HELP: &str = "Show the wallpaper as at the given local time of today, formatted as HH:MM or HH:MM:SS, instead of the current time. Time keeps passing from there on. Useful to preview the times of day of a dynamic wallpaper.";
+const OFFSET_HELP: &str = "Show the wallpaper shifted by the given time relative to the current time. Either a number of seconds or a combination of hours, minutes and seconds like `+1h30m`, `-2h` or `90s`.";
+const SPEED_HELP: &str = "How many times faster than real time the wallpaper is played. A speed of 1440 shows the whole day of a 24 hour wallpaper in one minute.";
+const LOOPS_HELP: &str = "How many times animated images like GIF, APNG or animated WebP are played before stopping at their last frame. By default animated WebP images are repeated as often as they ask for, all other animations forever.";
+const MAX_FPS_HELP: &str = "The highest rate in frames per second at which animated images are drawn. Frames which are shown shorter than this are skipped. Defaults to the refresh rate of the output.";
+const GENERATE_HELP: &str = "Write a Gnome dynamic wallpaper description showing the given images in order. Directories are replaced by the images they contain, ordered by --order and including subdirectories with --recursive. Either all images are shown equally long over --period starting at --start, or each image is fully shown at its own time of day given by --at. The description is printed unless --output is given.";
+const LOCATION_HELP: &str = "The geographic position used to calculate the position of the sun for solar wallpapers. Latitudes north and longitudes east are positive, e.g. `--latitude 52.52 --longitude 13.40`.";
 
 use clap::Parser;
 
@@ -127,16 +168,28 @@ use clap::Parser;
     author = AUTHOR,
     version = VERSION,
     about = DESC,
+    setting(clap::AppSettings::SubcommandsNegateReqs),
 )]
 pub struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
     #[clap(
         index = 1,
         help = "The file to display.",
         long_help = FILE_HELP,
         takes_value = true,
-        required = true,
+        required_unless_present = "color",
+    )]
+    file: Option<PathBuf>,
+    #[clap(
+        long = "color",
+        help = "A color or gradient to display instead of a file.",
+        long_help = COLOR_HELP,
+        takes_value = true,
+        value_name = "SPEC",
+        conflicts_with = "file",
     )]
-    file: PathBuf,
+    color: Option<Fill>,
     #[clap(
         arg_enum,
         short = 'f',
@@ -159,6 +212,69 @@ pub struct Args {
         ignore_case = true,
     )]
     scale: Scaling,
+    #[clap(
+        arg_enum,
+        long = "align",
+        help = "Where to place images on the screen.",
+        long_help = ALIGN_HELP,
+        default_value = "center",
+        takes_value = true,
+        ignore_case = true,
+    )]
+    align: Alignment,
+    #[clap(
+        long = "tile-scale",
+        help = "Factor by which tiled images are enlarged.",
+        long_help = TILE_SCALE_HELP,
+        default_value = "1",
+        takes_value = true,
+        validator = |value: &str| match value.parse::<f64>() {
+            Ok(scale) if scale > 0.0 => Ok(()),
+            _ => Err("The tile scale has to be a positive number"),
+        },
+    )]
+    tile_scale: f64,
+    #[clap(
+        long = "background",
+        help = "What to show around images not covering the screen.",
+        long_help = BACKGROUND_HELP,
+        default_value = "#000000",
+        takes_value = true,
+        value_name = "SPEC",
+    )]
+    background: Background,
+    #[clap(
+        long = "backdrop",
+        help = "What to show below transparent parts of the wallpaper.",
+        long_help = BACKDROP_HELP,
+        takes_value = true,
+        value_name = "SPEC",
+    )]
+    backdrop: Option<Source>,
+    #[clap(
+        long = "as-stored",
+        help = "Ignore the EXIF orientation and color profile of images.",
+        long_help = AS_STORED_HELP,
+        takes_value = false
+    )]
+    as_stored: bool,
+    #[clap(
+        arg_enum,
+        long = "dither",
+        help = "Dither the wallpaper to avoid banding.",
+        long_help = DITHER_HELP,
+        default_value = "none",
+        takes_value = true,
+        ignore_case = true,
+    )]
+    dither: Dither,
+    #[clap(
+        long = "gpu-scaling",
+        help = "Scale images on the GPU, sharing them between outputs.",
+        long_help = GPU_SCALING_HELP,
+        takes_value = false
+    )]
+    gpu_scaling: bool,
     #[clap(
         arg_enum,
         short = 'm',
@@ -169,6 +285,263 @@ pub struct Args {
         ignore_case = true,
     )]
     mode: Option<Mode>,
+    #[clap(
+        long = "latitude",
+        help = "Latitude in degrees for solar wallpapers.",
+        long_help = LOCATION_HELP,
+        takes_value = true,
+        allow_hyphen_values = true,
+        requires = "longitude",
+    )]
+    latitude: Option<f64>,
+    #[clap(
+        long = "longitude",
+        help = "Longitude in degrees for solar wallpapers.",
+        long_help = LOCATION_HELP,
+        takes_value = true,
+        allow_hyphen_values = true,
+        requires = "latitude",
+    )]
+    longitude: Option<f64>,
+    #[clap(
+        arg_enum,
+        long = "appearance",
+        help = "Preferred variant of wallpapers with a light and a dark variant.",
+        long_help = APPEARANCE_HELP,
+        default_value = "system",
+        takes_value = true,
+        ignore_case = true,
+    )]
+    appearance: AppearanceMode,
+    #[clap(
+        long = "light-from",
+        help = "Time of day from which on the light variant is shown.",
+        long_help = SCHEDULE_HELP,
+        default_value = "07:00",
+        takes_value = true,
+        parse(try_from_str = parse_time_of_day),
+    )]
+    light_from: NaiveTime,
+    #[clap(
+        long = "dark-from",
+        help = "Time of day from which on the dark variant is shown.",
+        long_help = SCHEDULE_HELP,
+        default_value = "19:00",
+        takes_value = true,
+        parse(try_from_str = parse_time_of_day),
+    )]
+    dark_from: NaiveTime,
+    #[clap(
+        arg_enum,
+        long = "clock",
+        help = "How time passes for dynamic wallpapers.",
+        long_help = CLOCK_HELP,
+        default_value = "wall",
+        takes_value = true,
+        ignore_case = true,
+    )]
+    clock: ClockMode,
+    #[clap(
+        long = "interval",
+        help = "Seconds each image of a slideshow is shown.",
+        long_help = INTERVAL_HELP,
+        default_value = "300",
+        takes_value = true,
+        validator = |value: &str| match value.parse::<f64>() {
+            Ok(interval) if interval > 0.0 => Ok(()),
+            _ => Err("The interval has to be a positive number of seconds"),
+        },
+    )]
+    interval: f64,
+    #[clap(
+        arg_enum,
+        long = "order",
+        help = "Order of the images of a slideshow.",
+        long_help = ORDER_HELP,
+        default_value = "name",
+        takes_value = true,
+        ignore_case = true,
+    )]
+    order: OrderMode,
+    #[clap(
+        long = "recursive",
+        help = "Include images in subdirectories of a slideshow.",
+        takes_value = false
+    )]
+    recursive: bool,
+    #[clap(
+        long = "time",
+        help = "Start from this time of day instead of now.",
+        long_help = TIME_HELP,
+        takes_value = true,
+        conflicts_with = "offset",
+        parse(try_from_str = parse_time_of_day),
+    )]
+    time: Option<NaiveTime>,
+    #[clap(
+        long = "offset",
+        help = "Shift the shown time relative to now.",
+        long_help = OFFSET_HELP,
+        takes_value = true,
+        allow_hyphen_values = true,
+        parse(try_from_str = parse_offset),
+    )]
+    offset: Option<i64>,
+    #[clap(
+        long = "speed",
+        help = "Factor by which the shown time passes faster.",
+        long_help = SPEED_HELP,
+        default_value = "1",
+        takes_value = true,
+        validator = |value: &str| match value.parse::<f64>() {
+            Ok(speed) if speed > 0.0 => Ok(()),
+            _ => Err("The speed has to be a positive number"),
+        },
+    )]
+    speed: f64,
+    #[clap(
+        long = "loops",
+        help = "Number of times animated images are played.",
+        long_help = LOOPS_HELP,
+        takes_value = true,
+    )]
+    loops: Option<u32>,
+    #[clap(
+        long = "max-fps",
+        help = "Highest frame rate of animated images.",
+        long_help = MAX_FPS_HELP,
+        takes_value = true,
+        validator = |value: &str| match value.parse::<f64>() {
+            Ok(fps) if fps > 0.0 => Ok(()),
+            _ => Err("The frame rate has to be a positive number"),
+        },
+    )]
+    max_fps: Option<f64>,
+}
+
+impl Args {
+    fn location(&self) -> Option<Location> {
+        match (self.latitude, self.longitude) {
+            (Some(latitude), Some(longitude)) => Some(Location {
+                latitude,
+                longitude,
+            }),
+            _ => None,
+        }
+    }
+
+    fn settings(&self) -> Settings {
+        let preference = match self.appearance {
+            AppearanceMode::Light => Preference::Fixed(Appearance::Light),
+            AppearanceMode::Dark => Preference::Fixed(Appearance::Dark),
+            AppearanceMode::System => Preference::System,
+            AppearanceMode::Schedule => Preference::Schedule {
+                light_from: self.light_from,
+                dark_from: self.dark_from,
+            },
+        };
+        Settings {
+            location: self.location(),
+            preference,
+            clock: match self.clock {
+                ClockMode::Wall => Clock::Wall,
+                ClockMode::Absolute => Clock::Absolute,
+            },
+            slideshow: SlideshowSettings {
+                interval: self.interval,
+                order: match self.order {
+                    OrderMode::Name => Order::Name,
+                    OrderMode::Mtime => Order::Mtime,
+                    OrderMode::Shuffle => Order::Shuffle,
+                },
+                recursive: self.recursive,
+            },
+        }
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            scaling: self.scale,
+            alignment: self.align,
+            tile_scale: self.tile_scale,
+            background: self.background,
+        }
+    }
+
+    fn virtual_clock(&self) -> VirtualClock {
+        let seek = match (self.time, self.offset) {
+            (Some(time), _) => Some(Seek::time(time)),
+            (None, Some(offset)) => Some(Seek::Offset(offset)),
+            (None, None) => None,
+        };
+        VirtualClock::new(seek, self.speed)
+    }
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    #[clap(about = "Validate a wallpaper without showing it.", long_about = CHECK_HELP)]
+    Check {
+        #[clap(help = "The file to check.", takes_value = true, required = true)]
+        file: PathBuf,
+    },
+    #[clap(about = "Write a dynamic wallpaper description for a list of images.", long_about = GENERATE_HELP)]
+    Generate {
+        #[clap(
+            help = "The images to show, in order.",
+            takes_value = true,
+            required = true,
+            min_values = 1
+        )]
+        images: Vec<PathBuf>,
+        #[clap(
+            long = "start",
+            help = "Time of day at which the first image is fully shown.",
+            default_value = "00:00",
+            takes_value = true,
+            parse(try_from_str = parse_time_of_day),
+        )]
+        start: NaiveTime,
+        #[clap(
+            long = "period",
+            help = "Time until the first image is shown again, e.g. `24h` or `90m`.",
+            default_value = "24h",
+            takes_value = true,
+            conflicts_with = "at",
+            parse(try_from_str = parse_duration),
+        )]
+        period: i64,
+        #[clap(
+            long = "at",
+            help = "Time of day at which the respective image is fully shown, given once per image.",
+            takes_value = true,
+            multiple_occurrences = true,
+            conflicts_with = "start",
+            parse(try_from_str = parse_time_of_day),
+        )]
+        at: Vec<NaiveTime>,
+        #[clap(
+            long = "transition",
+            help = "Length of each transition into the next image [default: half the time each image is shown].",
+            takes_value = true,
+            parse(try_from_str = parse_duration),
+        )]
+        transition: Option<i64>,
+        #[clap(
+            long = "type",
+            help = "Type of the transitions.",
+            default_value = "overlay",
+            takes_value = true
+        )]
+        kind: String,
+        #[clap(
+            short = 'o',
+            long = "output",
+            help = "The file to write the description to.",
+            takes_value = true
+        )]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(ArgEnum, Clone, Copy, Debug, Deserialize)]
@@ -177,11 +550,63 @@ pub enum Mode {
     Dynamic,
 }
 
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum AppearanceMode {
+    Light,
+    Dark,
+    System,
+    Schedule,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum ClockMode {
+    Wall,
+    Absolute,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum OrderMode {
+    Name,
+    Mtime,
+    Shuffle,
+}
+
 const CB_ERR_MSG: &str = "WlOutput Handler panicked. Cannot continue.";
 
 fn main() -> Result<(), ErrorReport> {
     let args = Args::parse();
     env_logger::init();
+    match &args.command {
+        Some(Command::Check { file }) => {
+            std::process::exit(check::run(file, &args.mode, &args.settings()));
+        }
+        Some(Command::Generate {
+            images,
+            start,
+            period,
+            at,
+            transition,
+            kind,
+            output,
+        }) => {
+            let timing = if at.is_empty() {
+                generate::Timing::Period {
+                    start: *start,
+                    period: *period as f64,
+                }
+            } else {
+                generate::Timing::Anchors(at.clone())
+            };
+            let options = generate::Options {
+                timing,
+                transition: transition.map(|secs| secs as f64),
+                kind: kind.clone(),
+                output: output.clone(),
+            };
+            std::process::exit(generate::run(images, &options, &args.settings()));
+        }
+        None => {}
+    }
     /*
      * Setup display initials for wayland
      */
@@ -243,13 +668,18 @@ fn main() -> Result<(), ErrorReport> {
         .sync_roundtrip(&mut (), |_, _, _| {})
         .map_err(|e| ApplicationError::io_error(e, line!(), file!()))?;
 
-    // Preliminary check for file existence for better errors
-    if !args.file.is_file() {
-        return Err(ApplicationError::NotAFile(args.file).into());
-    }
-
     // Read Metadata or Prepare Static Mode
-    let metadata = get_metadata_for_path(&args.mode, &args.file)?;
+    let metadata = match (&args.color, &args.file) {
+        (Some(fill), _) => MetadataReader::static_source(Source::fill(fill.clone())),
+        (None, Some(file)) => {
+            // Preliminary check for file existence for better errors
+            if !file.is_file() && !file.is_dir() {
+                return Err(ApplicationError::NotAFile(file.clone()).into());
+            }
+            get_metadata_for_path(&args.mode, file, &args.settings())?
+        }
+        (None, None) => unreachable!("The file or a color is required without a subcommand"),
+    };
 
     let result = worker::work(
         globals,
@@ -272,23 +702,42 @@ fn main() -> Result<(), ErrorReport> {
 fn get_metadata_for_path(
     mode: &Option<Mode>,
     path: &PathBuf,
+    settings: &Settings,
 ) -> Result<Metadata, ApplicationError> {
+    if path.is_dir() {
+        return Ok(MetadataReader::read_directory(path, settings)?);
+    }
     match mode {
         Some(Mode::Static) => Ok(MetadataReader::static_configuration(path)),
-        Some(Mode::Dynamic) => Ok(MetadataReader::read(path)?),
+        Some(Mode::Dynamic) if is_heic(path) => Ok(MetadataReader::read_heic(path, settings)?),
+        Some(Mode::Dynamic) => Ok(MetadataReader::read(path, settings)?),
         None => {
             debug!("Checking path {{ {:?} }}", path);
             let extension = path.extension();
-            if extension.is_some() && extension.unwrap() == "xml" {
-                Ok(MetadataReader::read(path)?)
-            } else if regex_is_match!(
-                r"\.(?i)(png|jpg|jpeg|gif|webp|farbfeld|tif|tiff|bmp|ico){1}$",
-                path.to_str().expect("Could not deciper given path")
-            ) {
+            if extension.is_some_and(|ext| ext == "xml" || ext == "toml" || ext == "json") {
+                Ok(MetadataReader::read(path, settings)?)
+            } else if is_heic(path) {
+                // Containers without the description of a dynamic desktop are still images
+                match MetadataReader::read_heic(path, settings) {
+                    Err(MetadataError::NoDynamicDesktop) => {
+                        Ok(MetadataReader::static_configuration(path))
+                    }
+                    metadata => Ok(metadata?),
+                }
+            } else if image::is_supported(path) {
                 Ok(MetadataReader::static_configuration(path))
+            } else if MetadataReader::is_description(path) {
+                Ok(MetadataReader::read(path, settings)?)
             } else {
                 Err(ApplicationError::InvalidDataType)
             }
         }
     }
 }
+
+fn is_heic(path: &Path) -> bool {
+    regex_is_match!(
+        r"\.(?i)(heic|heif){1}$",
+        path.to_str().expect("Could not deciper given path")
+    )
+}
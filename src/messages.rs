@@ -27,6 +27,8 @@ pub enum WorkerMessage {
     RemoveOutput(u32),
     AnimationStep(f32),
     AnimationStart(f64),
+    FrameTick,
     IPCConfigUpdate(crate::watchdog::ipc::Message),
+    AppearanceChanged(crate::metadata::Appearance),
     Refresh,
 }
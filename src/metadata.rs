@@ -14,39 +14,206 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod apple;
+mod calendar;
+mod diagnostic;
+mod easing;
+mod paths;
+mod schedule;
+mod slideshow;
+mod solar;
+mod themed;
+
 use chrono::naive::{NaiveDate, NaiveDateTime};
-use chrono::Local;
-use std::fs::OpenOptions;
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use clap::ArgEnum;
+use enkei::fill::Fill;
+use lazy_regex::regex_is_match;
+use log::{debug, warn};
+use std::io::Read;
 use std::path::PathBuf;
 use std::{ops::Range, path::Path};
 use thiserror::Error;
 
-use crate::schema::gnome_xml::{Background, Image};
+use crate::image::error::ImageError;
+use crate::image::scaling::{Alignment, Placement, Scaling};
+use crate::opengl::transition::TransitionKind;
+use crate::outputs::ScaledMode;
+use crate::schema::gnome_properties::{Wallpaper, Wallpapers};
+use crate::schema::gnome_xml::{Background, File, Image};
+use crate::schema::schedule::Schedule;
+
+use diagnostic::Element;
+
+pub use calendar::active as calendar_active;
+pub use calendar::CalendarSet;
+pub use diagnostic::Diagnostic;
+pub use easing::Easing;
+pub use slideshow::scan as scan_directory;
+pub use slideshow::{Order, Slideshow, SlideshowSettings};
+pub use solar::schedule as solar_schedule;
+pub use solar::{Location, SolarFrame, SolarPosition};
+pub use themed::{Appearance, Preference, Switch};
+
+/// User choices which influence how a wallpaper description is interpreted.
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub location: Option<Location>,
+    pub preference: Preference,
+    pub clock: Clock,
+    pub slideshow: SlideshowSettings,
+}
 
 pub struct MetadataReader {}
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Xml,
+    Json,
+    Toml,
+}
+
+impl Format {
+    // The suffix decides if it is known, otherwise the content has to give it away
+    fn detect(path: &Path, content: &str) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("xml") => return Some(Format::Xml),
+            Some("json") => return Some(Format::Json),
+            Some("toml") => return Some(Format::Toml),
+            _ => {}
+        }
+        let content = content.trim_start();
+        if content.starts_with('<') {
+            Some(Format::Xml)
+        } else if content.starts_with('{') {
+            Some(Format::Json)
+        } else if regex_is_match!(
+            r"(?m)^\s*(\[\[(anchors?|sets?)\]\]|(anchors?|sets?)\s*=)",
+            content
+        ) {
+            Some(Format::Toml)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum MetadataError {
     #[error("Invalid dynamic wallpaper definition. The first item has to be a time defintion in ymdhms.")]
     InvalidTimeFormat,
-    #[error("The first item must be a start time definition")]
-    InvalidTime,
     #[error("Could not open wallpaper description file: `{0}`")]
     CouldNotOpen(String),
     #[error("Could not parse wallpaper description: `{0}`")]
     CouldNotParse(String),
     #[error("Cannot determine current frame.")]
     CurrentFrame,
+    #[error("Invalid sun position `{0}`. Give either a phase (night, dawn, sunrise, noon, sunset, dusk) or an elevation together with an azimuth.")]
+    InvalidSolarPosition(String),
+    #[error("Solar wallpapers require a location, specify it via --latitude and --longitude.")]
+    MissingLocation,
+    #[error("Element {0} is no <solar> block. Solar wallpapers cannot be mixed with <static> and <transition> blocks.")]
+    MixedSolar(usize),
+    #[error("Could not decode embedded images: `{0}`")]
+    CouldNotDecode(Box<ImageError>),
+    #[error("The container holds no dynamic desktop description.")]
+    NoDynamicDesktop,
+    #[error("The background properties list no wallpaper.")]
+    NoWallpaper,
+    #[error("Unknown easing `{0}`. Choose one of linear, quad-in, quad-out, quad-inout, cubic-in, cubic-out, cubic-inout, sine-in, sine-out, sine-inout.")]
+    InvalidEasing(String),
+    #[error("The directory contains no supported images.")]
+    NoImages,
+    #[error("The description {0:?} references itself, directly or through other descriptions.")]
+    Cycle(PathBuf),
+    #[error("{0}")]
+    Invalid(Box<Diagnostic>),
 }
 
 impl MetadataReader {
-    pub fn read<P: AsRef<Path>>(path: P) -> Result<Metadata, MetadataError> {
-        let config_file = OpenOptions::new()
-            .read(true)
-            .open(path)
+    pub fn read<P: AsRef<Path>>(path: P, settings: &Settings) -> Result<Metadata, MetadataError> {
+        let (metadata, issues) = MetadataReader::read_checked(path, settings)?;
+        for issue in issues {
+            warn!("{issue}");
+        }
+        Ok(metadata)
+    }
+
+    /// Like [MetadataReader::read], but returns inconsistencies which do not prevent showing the wallpaper.
+    pub fn read_checked<P: AsRef<Path>>(
+        path: P,
+        settings: &Settings,
+    ) -> Result<(Metadata, Vec<String>), MetadataError> {
+        let mut issues = Vec::new();
+        let metadata = MetadataReader::read_inner(path.as_ref(), &[], settings, &mut issues)?;
+        Ok((metadata, issues))
+    }
+
+    /// Like [MetadataReader::read_checked], for a description of `path` which is not read from disk.
+    pub fn read_content<P: AsRef<Path>>(
+        path: P,
+        content: &str,
+        settings: &Settings,
+    ) -> Result<(Metadata, Vec<String>), MetadataError> {
+        let mut issues = Vec::new();
+        let metadata =
+            MetadataReader::read_str(path.as_ref(), content, &[], settings, &mut issues)?;
+        Ok((metadata, issues))
+    }
+
+    /// `parents` are the descriptions referencing this one, directly or through others.
+    fn read_inner(
+        path: &Path,
+        parents: &[PathBuf],
+        settings: &Settings,
+        issues: &mut Vec<String>,
+    ) -> Result<Metadata, MetadataError> {
+        let content = std::fs::read_to_string(path)
             .map_err(|e| MetadataError::CouldNotOpen(format!("{}", e)))?;
-        let config: Background = serde_xml_rs::from_reader(config_file)
-            .map_err(|e| MetadataError::CouldNotParse(format!("{}", e)))?;
+        MetadataReader::read_str(path, &content, parents, settings, issues)
+    }
+
+    fn read_str(
+        path: &Path,
+        content: &str,
+        parents: &[PathBuf],
+        settings: &Settings,
+        issues: &mut Vec<String>,
+    ) -> Result<Metadata, MetadataError> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if parents.contains(&canonical) {
+            return Err(MetadataError::Cycle(path.to_path_buf()));
+        }
+        let parents = &[parents, &[canonical]].concat();
+        // Paths in the description are relative to its own location
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        match Format::detect(path, content) {
+            Some(Format::Xml) | None => {}
+            Some(Format::Json) => {
+                let config: Schedule = serde_json::from_str(content)
+                    .map_err(|e| MetadataError::CouldNotParse(format!("{}", e)))?;
+                return MetadataReader::read_schedule(config, base, parents, settings, issues);
+            }
+            Some(Format::Toml) => {
+                let config: Schedule = toml::from_str(content)
+                    .map_err(|e| MetadataError::CouldNotParse(format!("{}", e)))?;
+                return MetadataReader::read_schedule(config, base, parents, settings, issues);
+            }
+        }
+        // Both formats share the file suffix, only the root element tells them apart
+        if regex_is_match!(r"<wallpapers[\s>]", content) {
+            let (properties, _) =
+                diagnostic::parse_xml::<Wallpapers, Wallpaper>(path, content, Some("wallpaper"))?;
+            return MetadataReader::read_properties(properties, base, parents, settings, issues);
+        }
+        let (config, elements) = diagnostic::parse_xml::<Background, Image>(path, content, None)?;
+        if config
+            .images
+            .iter()
+            .any(|image| matches!(image, Image::Solar { .. }))
+        {
+            return MetadataReader::read_solar(config, base, settings.location);
+        }
         // Sanity Checks and Transition
         let mut transitions = vec![];
         let start_time = {
@@ -59,44 +226,83 @@ impl MetadataReader {
                 second,
             } = config
                 .images
-                .get(0)
+                .first()
                 .ok_or(MetadataError::InvalidTimeFormat)?
             {
                 NaiveDate::from_ymd(*year as i32, *month, *day).and_hms(*hour, *minute, *second)
             } else {
-                return Err(MetadataError::InvalidTime);
+                return Err(unexpected(
+                    path,
+                    &elements,
+                    0,
+                    "<starttime>",
+                    "The first element has to be a <starttime> giving the moment the timeline starts at.",
+                ));
             }
         };
         let mut elapsed = 0f64;
 
-        let mut entry_iter = config.images.iter().skip(1).peekable();
+        let mut entry_iter = config.images.iter().enumerate().skip(1).peekable();
 
-        while let Some(next) = entry_iter.next() {
+        while let Some((idx, next)) = entry_iter.next() {
             let from_file;
             let duration_static;
             let mut duration_transition = 0_f64;
 
             if let Image::Static { duration, file } = &next {
                 duration_static = *duration;
-                from_file = file.clone();
+                from_file = Source::from_file(file.clone(), base, &entry("static", idx, "file"))?;
+                if duration_static <= 0.0 {
+                    issues.push(format!(
+                        "{} is not positive: {duration_static}s",
+                        entry("static", idx, "duration")
+                    ));
+                }
             } else {
-                return Err(MetadataError::CouldNotParse(format!(
-                    "Was expecting <static> block but found instead: {:#?}",
-                    next
-                )));
+                return Err(unexpected(
+                    path,
+                    &elements,
+                    idx,
+                    "a <static> block",
+                    "Each <transition> has to follow the <static> block showing its <from> image.",
+                ));
             }
 
-            if let Some(Image::Transition {
-                duration, to, kind, ..
-            }) = entry_iter.peek()
+            if let Some((
+                idx,
+                Image::Transition {
+                    duration,
+                    from,
+                    to,
+                    kind,
+                },
+            )) = entry_iter.peek()
             {
-                let kind_trans = kind.clone();
-                let to_file = to.clone();
+                let from =
+                    Source::from_file(from.clone(), base, &entry("transition", *idx, "from"))?;
+                if from != from_file {
+                    issues.push(format!(
+                        "{} is `{}` but the preceding <static> block shows `{}`",
+                        entry("transition", *idx, "from"),
+                        from,
+                        from_file
+                    ));
+                }
+                let kind_trans = transition_kind(kind, &entry("transition", *idx, "type"), issues);
+                let to_file =
+                    Source::from_file(to.clone(), base, &entry("transition", *idx, "to"))?;
                 duration_transition = *duration;
+                if duration_transition <= 0.0 {
+                    issues.push(format!(
+                        "{} is not positive: {duration_transition}s",
+                        entry("transition", *idx, "duration")
+                    ));
+                }
 
                 let duration = elapsed + duration_static + duration_transition;
                 transitions.push(Transition::WithAnimation {
                     kind: kind_trans,
+                    easing: Easing::default(),
                     from: from_file,
                     to: to_file,
                     time_range: (elapsed..duration),
@@ -120,43 +326,474 @@ impl MetadataReader {
             acc + elem.duration_static() + elem.duration_transition()
         });
 
-        let meta_config = Metadata {
+        let meta_config = Metadata::Timeline {
             start_time,
             image_transisitons: transitions,
             total_duration_sec,
+            clock: settings.clock,
         };
         Ok(meta_config)
     }
 
+    /// Whether the file is a wallpaper description in any of the supported formats.
+    pub fn is_description<P: AsRef<Path>>(path: P) -> bool {
+        // SVG images are XML too
+        if crate::image::is_supported(path.as_ref()) {
+            return false;
+        }
+        // Only the beginning is inspected, images may be large
+        let mut head = Vec::new();
+        let read = std::fs::File::open(path.as_ref())
+            .and_then(|file| file.take(4096).read_to_end(&mut head));
+        read.is_ok() && Format::detect(path.as_ref(), &String::from_utf8_lossy(&head)).is_some()
+    }
+
+    /// Cycle through the images of a directory.
+    pub fn read_directory<P: Into<PathBuf>>(
+        path: P,
+        settings: &Settings,
+    ) -> Result<Metadata, MetadataError> {
+        let slideshow = Slideshow::new(path.into(), settings.slideshow)?;
+        if slideshow.images().is_empty() {
+            return Err(MetadataError::NoImages);
+        }
+        Ok(Metadata::Slideshow(slideshow))
+    }
+
+    // Dynamic desktops of macOS, the frames and their description are stored in one HEIC container
+    pub fn read_heic<P: AsRef<Path>>(
+        path: P,
+        settings: &Settings,
+    ) -> Result<Metadata, MetadataError> {
+        apple::read(path, settings.location)
+    }
+
+    // Entries of gnome-background-properties, the variants may be images or dynamic wallpapers themselves
+    fn read_properties(
+        properties: Wallpapers,
+        base: &Path,
+        parents: &[PathBuf],
+        settings: &Settings,
+        issues: &mut Vec<String>,
+    ) -> Result<Metadata, MetadataError> {
+        let mut wallpapers = properties.wallpapers.into_iter().filter(|w| !w.deleted);
+        let wallpaper = wallpapers.next().ok_or(MetadataError::NoWallpaper)?;
+        if wallpapers.next().is_some() {
+            warn!(
+                "The background properties list multiple wallpapers, showing only the first {{ name: {:?} }}",
+                wallpaper.name
+            );
+        }
+        debug!(
+            "Found background properties {{ name: {:?}, filename: {:?}, filename-dark: {:?} }}",
+            wallpaper.name, wallpaper.filename, wallpaper.filename_dark
+        );
+        let light = MetadataReader::read_variant(
+            paths::resolve(&wallpaper.filename, base),
+            parents,
+            settings,
+            issues,
+        )?;
+        let mut metadata = if let Some(dark) = wallpaper.filename_dark {
+            Metadata::Themed {
+                light: Box::new(light),
+                dark: Box::new(MetadataReader::read_variant(
+                    paths::resolve(&dark, base),
+                    parents,
+                    settings,
+                    issues,
+                )?),
+                preference: settings.preference,
+                switch: themed::initial_switch(&settings.preference),
+            }
+        } else {
+            light
+        };
+        if let Some(options) = wallpaper.options {
+            metadata.adjust(Adjustment {
+                scaling: picture_options(options.trim(), issues),
+                alignment: None,
+            });
+        }
+        Ok(metadata)
+    }
+
+    fn read_schedule(
+        config: Schedule,
+        base: &Path,
+        parents: &[PathBuf],
+        settings: &Settings,
+        issues: &mut Vec<String>,
+    ) -> Result<Metadata, MetadataError> {
+        if config.sets.is_empty() {
+            return schedule::read(config.anchors, base, issues);
+        }
+        // Anchors next to sets apply on all dates no set matches
+        let fallback = if config.anchors.is_empty() {
+            None
+        } else {
+            Some(schedule::read(config.anchors, base, issues)?)
+        };
+        calendar::read(config.sets, fallback, base, parents, settings, issues)
+    }
+
+    // Wallpapers referenced by other descriptions
+    fn read_variant(
+        path: PathBuf,
+        parents: &[PathBuf],
+        settings: &Settings,
+        issues: &mut Vec<String>,
+    ) -> Result<Metadata, MetadataError> {
+        if path.is_dir() {
+            MetadataReader::read_directory(&path, settings)
+        } else if path.extension().is_some_and(|ext| ext == "xml")
+            || MetadataReader::is_description(&path)
+        {
+            MetadataReader::read_inner(&path, parents, settings, issues)
+        } else {
+            Ok(MetadataReader::static_configuration(path))
+        }
+    }
+
+    fn read_solar(
+        config: Background,
+        base: &Path,
+        location: Option<Location>,
+    ) -> Result<Metadata, MetadataError> {
+        let location = location.ok_or(MetadataError::MissingLocation)?;
+        let frames = config
+            .images
+            .into_iter()
+            .enumerate()
+            .map(|(idx, image)| match image {
+                Image::Solar {
+                    file,
+                    elevation,
+                    azimuth,
+                    phase,
+                    transition,
+                } => {
+                    let position = match (phase, elevation, azimuth) {
+                        (Some(phase), None, None) => SolarPosition::from_phase(&phase)?,
+                        (None, Some(elevation), Some(azimuth)) => {
+                            SolarPosition::from_coordinates(elevation, azimuth)
+                        }
+                        _ => {
+                            return Err(MetadataError::InvalidSolarPosition(format!("{:?}", file)))
+                        }
+                    };
+                    Ok(SolarFrame {
+                        file: Source::from_file(file, base, &entry("solar", idx, "file"))?,
+                        position,
+                        transition,
+                    })
+                }
+                _ => Err(MetadataError::MixedSolar(idx + 1)),
+            })
+            .collect::<Result<Vec<SolarFrame>, MetadataError>>()?;
+        Ok(Metadata::Solar { frames, location })
+    }
+
     // This is a workaround to create some basic description if only an image is given as a background
     pub fn static_configuration<P: Into<PathBuf>>(path: P) -> Metadata {
-        Metadata {
+        MetadataReader::static_source(Source::new(path.into()))
+    }
+
+    /// Show a single source, such as a color, for good.
+    pub fn static_source(source: Source) -> Metadata {
+        Metadata::Timeline {
             start_time: Local::now().naive_local(),
             total_duration_sec: f64::MAX,
+            clock: Clock::Wall,
             image_transisitons: vec![Transition::WithoutAnimation {
                 // Duration is given in nanoseconds in the std, we have to go a bit smaller than that to not panic
                 duration: (u64::MAX / 10) as f64,
                 time_range: 0f64..f64::MAX,
-                from: path.into(),
+                from: source,
             }],
         }
     }
 }
 
+/// Placement given for single entries of a wallpaper, taking precedence over the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Adjustment {
+    pub scaling: Option<Scaling>,
+    pub alignment: Option<Alignment>,
+}
+
+impl Adjustment {
+    /// Read the `scale` and `align` options of the entry `entry`.
+    pub(super) fn parse(
+        scaling: Option<&str>,
+        alignment: Option<&str>,
+        entry: &str,
+    ) -> Result<Self, MetadataError> {
+        Ok(Self {
+            scaling: scaling
+                .map(|name| {
+                    Scaling::from_str(name, true).map_err(|_| {
+                        MetadataError::CouldNotParse(format!(
+                            "Unknown scaling `{name}` of {entry}, choose one of fill, fit, none, stretch, tile"
+                        ))
+                    })
+                })
+                .transpose()?,
+            alignment: alignment
+                .map(|name| {
+                    Alignment::from_str(name, true).map_err(|_| {
+                        MetadataError::CouldNotParse(format!(
+                            "Unknown alignment `{name}` of {entry}, choose one of center, top, bottom, left, right, top-left, top-right, bottom-left, bottom-right"
+                        ))
+                    })
+                })
+                .transpose()?,
+        })
+    }
+
+    // Options of an entry already set by a nested wallpaper are kept
+    fn or(self, outer: Adjustment) -> Self {
+        Self {
+            scaling: self.scaling.or(outer.scaling),
+            alignment: self.alignment.or(outer.alignment),
+        }
+    }
+}
+
+/// An image which may be available in several resolutions, or a color drawn instead.
+#[derive(Debug, Clone)]
+pub struct Source {
+    path: Option<PathBuf>,
+    sizes: Vec<(u32, u32, PathBuf)>,
+    fill: Option<Fill>,
+    adjustment: Adjustment,
+    // The entry of the description giving the source, to point at it if the
+    // image cannot be loaded
+    entry: Option<String>,
+}
+
+// Sources showing the same images are equal, wherever they are given
+impl PartialEq for Source {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+            && self.sizes == other.sizes
+            && self.fill == other.fill
+            && self.adjustment == other.adjustment
+    }
+}
+
+impl Source {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path: Some(path),
+            sizes: Vec::new(),
+            fill: None,
+            adjustment: Adjustment::default(),
+            entry: None,
+        }
+    }
+
+    pub fn fill(fill: Fill) -> Self {
+        Self {
+            path: None,
+            sizes: Vec::new(),
+            fill: Some(fill),
+            adjustment: Adjustment::default(),
+            entry: None,
+        }
+    }
+
+    pub fn adjusted(mut self, adjustment: Adjustment) -> Self {
+        self.adjust(adjustment);
+        self
+    }
+
+    fn adjust(&mut self, adjustment: Adjustment) {
+        self.adjustment = self.adjustment.or(adjustment);
+    }
+
+    /// The placement of this source, its own options replace those of `placement`.
+    pub fn placement(&self, placement: Placement) -> Placement {
+        Placement {
+            scaling: self.adjustment.scaling.unwrap_or(placement.scaling),
+            alignment: self.adjustment.alignment.unwrap_or(placement.alignment),
+            ..placement
+        }
+    }
+
+    /// Read a file entry of a description, which may also give a color or gradient.
+    pub(super) fn resolve(path: &Path, base: &Path, entry: &str) -> Result<Self, MetadataError> {
+        let spec = path.to_string_lossy();
+        if Fill::is_fill(&spec) {
+            let fill = spec.parse().map_err(|e| {
+                MetadataError::CouldNotParse(format!("Invalid color in {entry}: {e}"))
+            })?;
+            Ok(Self::fill(fill))
+        } else {
+            Ok(Self {
+                entry: Some(entry.into()),
+                ..Self::new(paths::resolve(path, base))
+            })
+        }
+    }
+
+    /// The entry of the description giving this source, `None` if it was not
+    /// read from a description.
+    pub fn entry(&self) -> Option<&str> {
+        self.entry.as_deref()
+    }
+
+    /// The color or gradient drawn instead of an image.
+    pub fn as_fill(&self) -> Option<&Fill> {
+        self.fill.as_ref()
+    }
+
+    /// Choose the smallest variant covering the whole output, or the largest one if none is big enough.
+    pub fn best_for(&self, mode: &ScaledMode) -> &PathBuf {
+        let area = |(width, height, _): &&(u32, u32, PathBuf)| *width as u64 * *height as u64;
+        let covering = self
+            .sizes
+            .iter()
+            .filter(|(width, height, _)| {
+                *width as i64 >= mode.width as i64 && *height as i64 >= mode.height as i64
+            })
+            .min_by_key(area);
+        covering
+            .or_else(|| self.sizes.iter().max_by_key(area))
+            .map(|(_, _, path)| path)
+            .or(self.path.as_ref())
+            .expect("A source has at least one variant")
+    }
+
+    /// All files of this source.
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.path
+            .iter()
+            .chain(self.sizes.iter().map(|(_, _, path)| path))
+    }
+
+    /// Resolve all variants of a file read from the description at `entry` relative to `base`.
+    fn from_file(file: File, base: &Path, entry: &str) -> Result<Self, MetadataError> {
+        if file.sizes.is_empty() {
+            return match file.path {
+                Some(path) => Source::resolve(&path, base, entry),
+                None => Err(MetadataError::CouldNotParse(format!(
+                    "Encountered file without path or <size> variants in {entry}"
+                ))),
+            };
+        }
+        Ok(Self {
+            path: file.path.map(|path| paths::resolve(&path, base)),
+            sizes: file
+                .sizes
+                .into_iter()
+                .map(|size| (size.width, size.height, paths::resolve(&size.path, base)))
+                .collect(),
+            fill: None,
+            adjustment: Adjustment::default(),
+            entry: Some(entry.into()),
+        })
+    }
+}
+
+// Given on the command line, either a color or gradient or the path of an image
+impl std::str::FromStr for Source {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        if Fill::is_fill(spec) {
+            spec.parse().map(Self::fill)
+        } else {
+            match Path::new(spec).canonicalize() {
+                Ok(path) if crate::image::is_supported(&path) => Ok(Self::new(path)),
+                Ok(path) => Err(format!("{:?} is no supported image", path)),
+                Err(_) => Err(format!("No file found at `{spec}`")),
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(fill) = &self.fill {
+            return write!(f, "{fill}");
+        }
+        let paths: Vec<String> = self
+            .paths()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        f.write_str(&paths.join(", "))
+    }
+}
+
+// Human readable position of an element in the description, e.g. `<transition> #4 <to>`
+fn entry(element: &str, index: usize, field: &str) -> String {
+    format!("<{element}> #{} <{field}>", index + 1)
+}
+
+// The elements found by the parser may not line up with the deserialized ones,
+// the message then lacks the position in the file
+fn unexpected(
+    path: &Path,
+    elements: &[Element],
+    idx: usize,
+    expected: &str,
+    hint: &str,
+) -> MetadataError {
+    let diagnostic = match elements.get(idx) {
+        Some(element) => Diagnostic::new(
+            path,
+            format!("expected {expected}, found <{}>", element.name),
+        )
+        .element(element),
+        None => Diagnostic::new(path, format!("expected {expected}")),
+    };
+    diagnostic.hint(hint).into()
+}
+
+// Unknown transition types fall back to `overlay`, which is what Gnome would do
+fn transition_kind(name: &str, entry: &str, issues: &mut Vec<String>) -> TransitionKind {
+    TransitionKind::from_name(name).unwrap_or_else(|| {
+        issues.push(format!(
+            "Unknown transition type `{name}` of {entry}, falling back to overlay"
+        ));
+        TransitionKind::default()
+    })
+}
+
+// Gnome's picture options, spanning over all outputs is not supported and filled per output instead
+fn picture_options(options: &str, issues: &mut Vec<String>) -> Option<Scaling> {
+    match options {
+        "zoom" | "spanned" => Some(Scaling::Fill),
+        "scaled" => Some(Scaling::Fit),
+        "centered" => Some(Scaling::None),
+        "stretched" => Some(Scaling::Stretch),
+        "wallpaper" => Some(Scaling::Tile),
+        "none" => None,
+        other => {
+            issues.push(format!(
+                "Unknown picture option `{other}` of <wallpaper> <options>, using --scale instead"
+            ));
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Transition {
     WithAnimation {
-        kind: String,
+        kind: TransitionKind,
+        easing: Easing,
         duration_static: f64,
         duration_transition: f64,
         time_range: Range<f64>,
-        from: PathBuf,
-        to: PathBuf,
+        from: Source,
+        to: Source,
     },
     WithoutAnimation {
         duration: f64,
         time_range: Range<f64>,
-        from: PathBuf,
+        from: Source,
     },
 }
 
@@ -194,14 +831,28 @@ impl Transition {
         }
     }
 
-    pub fn from(&self) -> &PathBuf {
+    pub fn from(&self) -> &Source {
         match self {
             Transition::WithAnimation { from, .. } => from,
             Transition::WithoutAnimation { from, .. } => from,
         }
     }
 
-    pub fn to(&self) -> Option<&PathBuf> {
+    pub fn kind(&self) -> Option<TransitionKind> {
+        match self {
+            Transition::WithAnimation { kind, .. } => Some(*kind),
+            Transition::WithoutAnimation { .. } => None,
+        }
+    }
+
+    pub fn easing(&self) -> Easing {
+        match self {
+            Transition::WithAnimation { easing, .. } => *easing,
+            Transition::WithoutAnimation { .. } => Easing::default(),
+        }
+    }
+
+    pub fn to(&self) -> Option<&Source> {
         match self {
             Transition::WithAnimation { to, .. } => Some(to),
             Transition::WithoutAnimation { .. } => None,
@@ -209,11 +860,77 @@ impl Transition {
     }
 }
 
+/// How the time since the start of a timeline is counted.
+#[derive(PartialEq, Debug, Clone, Copy, Hash, Eq)]
+pub enum Clock {
+    /// Follow the local wall clock, a daily cycle starts at the same time of day regardless of
+    /// changes to daylight saving time or the time zone.
+    Wall,
+    /// Count the time which actually passed since the start, like Gnome does.
+    Absolute,
+}
+
+impl Clock {
+    /// Seconds passed from the local `start` until `now`.
+    pub fn elapsed(&self, start: &NaiveDateTime, now: DateTime<Utc>) -> f64 {
+        let elapsed = match self {
+            Clock::Wall => now.with_timezone(&Local).naive_local() - *start,
+            Clock::Absolute => now - localize(start),
+        };
+        elapsed.num_milliseconds() as f64 / 1000.0
+    }
+
+    /// The moment `offset` seconds after the local `start`, `None` if it is skipped on the wall clock.
+    pub fn moment(&self, start: &NaiveDateTime, offset: Duration) -> Option<DateTime<Local>> {
+        match self {
+            Clock::Wall => Local.from_local_datetime(&(*start + offset)).earliest(),
+            Clock::Absolute => Some((localize(start) + offset).with_timezone(&Local)),
+        }
+    }
+}
+
+// Times skipped by daylight saving time are interpreted with the offset before the change
+pub fn localize(time: &NaiveDateTime) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(time)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&(*time - Local.offset_from_utc_datetime(time))))
+}
+
+/// Reload the time zone cached by the C library from `TZ` and `/etc/localtime`.
+///
+/// Safe to call while other threads compute local times, as the watchdog does: tzset, mktime
+/// and localtime_r of glibc and musl share a lock around the zone. Only changing `TZ` with
+/// setenv would race with them, which enkei never does.
+pub fn reload_time_zone() {
+    // libc has no binding of tzset, but mktime is required to behave as if it was called
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    tm.tm_mday = 1;
+    tm.tm_year = 70;
+    unsafe { libc::mktime(&mut tm) };
+}
+
 #[derive(Debug, Clone)]
-pub struct Metadata {
-    start_time: NaiveDateTime,
-    image_transisitons: Vec<Transition>,
-    total_duration_sec: f64,
+pub enum Metadata {
+    Timeline {
+        start_time: NaiveDateTime,
+        image_transisitons: Vec<Transition>,
+        total_duration_sec: f64,
+        clock: Clock,
+    },
+    Solar {
+        frames: Vec<SolarFrame>,
+        location: Location,
+    },
+    Themed {
+        light: Box<Metadata>,
+        dark: Box<Metadata>,
+        preference: Preference,
+        switch: Switch,
+    },
+    Slideshow(Slideshow),
+    Calendar(Vec<CalendarSet>),
 }
 
 pub enum AnimationState {
@@ -221,13 +938,184 @@ pub enum AnimationState {
     Transition(f64, Transition),
 }
 
+impl AnimationState {
+    pub fn transition(&self) -> &Transition {
+        match self {
+            AnimationState::Static(_, t) => t,
+            AnimationState::Transition(_, t) => t,
+        }
+    }
+
+    /// A short blend between the images of two different wallpapers.
+    fn crossfade(from: Source, to: Source, progress: f64, duration: f64) -> Self {
+        AnimationState::Transition(
+            progress,
+            Transition::WithAnimation {
+                kind: TransitionKind::default(),
+                easing: Easing::default(),
+                duration_static: 0.0,
+                duration_transition: duration,
+                time_range: 0.0..duration,
+                from,
+                to,
+            },
+        )
+    }
+
+    /// Ends a static state after `until` seconds at the latest, to refresh the shown image then.
+    fn clipped(self, until: f64) -> Self {
+        match self {
+            AnimationState::Static(progress, transition)
+                if until < transition.duration_static() - progress =>
+            {
+                AnimationState::Static(
+                    progress,
+                    Transition::WithoutAnimation {
+                        duration: progress + until,
+                        time_range: transition.time_range().clone(),
+                        from: transition.from().clone(),
+                    },
+                )
+            }
+            state => state,
+        }
+    }
+}
+
 impl Metadata {
-    pub fn current(&self) -> Result<AnimationState, MetadataError> {
-        let now = Local::now().naive_local();
+    pub fn current_at(&self, now: DateTime<Utc>) -> Result<AnimationState, MetadataError> {
+        match self {
+            Metadata::Timeline {
+                start_time,
+                image_transisitons,
+                total_duration_sec,
+                clock,
+            } => Metadata::current_timeline(
+                start_time,
+                image_transisitons,
+                *total_duration_sec,
+                clock,
+                now,
+            ),
+            Metadata::Solar { frames, location } => solar::current(frames, location, now),
+            Metadata::Themed {
+                light,
+                dark,
+                preference,
+                switch,
+            } => themed::current(light, dark, preference, switch, now),
+            Metadata::Slideshow(slideshow) => slideshow.current(now),
+            Metadata::Calendar(sets) => calendar::current(sets, now),
+        }
+    }
+
+    /// Apply the options of an entry to all images of this wallpaper, which do not set them themselves.
+    pub fn adjust(&mut self, adjustment: Adjustment) {
+        match self {
+            Metadata::Timeline {
+                image_transisitons, ..
+            } => {
+                for transition in image_transisitons.iter_mut() {
+                    match transition {
+                        Transition::WithAnimation { from, to, .. } => {
+                            from.adjust(adjustment);
+                            to.adjust(adjustment);
+                        }
+                        Transition::WithoutAnimation { from, .. } => from.adjust(adjustment),
+                    }
+                }
+            }
+            Metadata::Solar { frames, .. } => {
+                for frame in frames.iter_mut() {
+                    frame.file.adjust(adjustment);
+                }
+            }
+            Metadata::Themed { light, dark, .. } => {
+                light.adjust(adjustment);
+                dark.adjust(adjustment);
+            }
+            Metadata::Slideshow(slideshow) => slideshow.adjust(adjustment),
+            Metadata::Calendar(sets) => {
+                for set in sets.iter_mut() {
+                    set.wallpaper.adjust(adjustment);
+                }
+            }
+        }
+    }
+
+    /// Pick up changes of the images shown by slideshows.
+    pub fn rescan(&mut self, now: DateTime<Utc>) {
+        match self {
+            Metadata::Slideshow(slideshow) => slideshow.rescan(now),
+            Metadata::Themed { light, dark, .. } => {
+                light.rescan(now);
+                dark.rescan(now);
+            }
+            Metadata::Calendar(sets) => {
+                for set in sets.iter_mut() {
+                    set.wallpaper.rescan(now);
+                }
+            }
+            Metadata::Timeline { .. } | Metadata::Solar { .. } => {}
+        }
+    }
+
+    /// Leave out an image of a slideshow which cannot be loaded, returns whether any slideshow shows it.
+    pub fn skip(&mut self, path: &Path, now: DateTime<Utc>) -> bool {
+        match self {
+            Metadata::Slideshow(slideshow) => slideshow.skip(path, now),
+            Metadata::Themed { light, dark, .. } => {
+                // Both variants may show the same directory
+                light.skip(path, now) | dark.skip(path, now)
+            }
+            Metadata::Calendar(sets) => {
+                let mut found = false;
+                for set in sets.iter_mut() {
+                    found |= set.wallpaper.skip(path, now);
+                }
+                found
+            }
+            Metadata::Timeline { .. } | Metadata::Solar { .. } => false,
+        }
+    }
+
+    /// Update the color scheme followed by themed wallpapers, returns whether the shown variant changes.
+    pub fn set_system_appearance(&mut self, appearance: Appearance, now: DateTime<Utc>) -> bool {
+        match self {
+            Metadata::Themed {
+                preference: Preference::System,
+                switch,
+                ..
+            } if switch.appearance != appearance => {
+                *switch = Switch {
+                    appearance,
+                    at: now,
+                };
+                true
+            }
+            Metadata::Calendar(sets) => {
+                // Every set has to follow, not only the one shown right now
+                let mut changed = false;
+                for set in sets.iter_mut() {
+                    changed |= set.wallpaper.set_system_appearance(appearance, now);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
 
-        let diff = (now - self.start_time).num_seconds() as f64 % self.total_duration_sec;
-        let cur = self
-            .image_transisitons
+    fn current_timeline(
+        start_time: &NaiveDateTime,
+        image_transisitons: &[Transition],
+        total_duration_sec: f64,
+        clock: &Clock,
+        now: DateTime<Utc>,
+    ) -> Result<AnimationState, MetadataError> {
+        let diff = clock
+            .elapsed(start_time, now)
+            .rem_euclid(total_duration_sec);
+        let cur = image_transisitons
             .iter()
             .find(|elem| elem.time_range().contains(&diff))
             .ok_or(MetadataError::CurrentFrame)?;
@@ -0,0 +1,147 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use chrono::Local;
+use lazy_regex::regex;
+use log::debug;
+use serde::de::DeserializeOwned;
+
+use super::{
+    Clock, Easing, Location, Metadata, MetadataError, SolarFrame, SolarPosition, Source, Transition,
+};
+use crate::opengl::transition::TransitionKind;
+use crate::schema::apple_desktop::{Solar, H24};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+pub fn read<P: AsRef<Path>>(
+    path: P,
+    location: Option<Location>,
+) -> Result<Metadata, MetadataError> {
+    let container = crate::image::heic::extract(path)
+        .map_err(|e| MetadataError::CouldNotDecode(Box::new(e)))?;
+    let xmp = container.xmp.ok_or(MetadataError::NoDynamicDesktop)?;
+
+    let solar = attribute::<Solar>(&xmp, "solar")?;
+    let h24 = attribute::<H24>(&xmp, "h24")?;
+    debug!(
+        "Found dynamic desktop {{ solar: {}, h24: {} }}",
+        solar.is_some(),
+        h24.is_some()
+    );
+    // Some wallpapers carry both descriptions, the sun position is preferred if we know where we are
+    match (solar, h24, location) {
+        (Some(solar), _, Some(location)) => from_solar(solar, location, &container.frames),
+        (_, Some(h24), _) => from_h24(h24, &container.frames),
+        (Some(_), None, None) => Err(MetadataError::MissingLocation),
+        (None, None, _) => Err(MetadataError::NoDynamicDesktop),
+    }
+}
+
+// The property lists are stored base64 encoded as attributes of the XMP description
+fn attribute<T: DeserializeOwned>(xmp: &str, name: &str) -> Result<Option<T>, MetadataError> {
+    let found = regex!(r#"apple_desktop:(\w+)="([^"]+)""#)
+        .captures_iter(xmp)
+        .find(|caps| &caps[1] == name);
+    if let Some(caps) = found {
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&caps[2])
+            .map_err(|e| MetadataError::CouldNotParse(format!("{}", e)))?;
+        let list =
+            plist::from_bytes(&data).map_err(|e| MetadataError::CouldNotParse(format!("{}", e)))?;
+        Ok(Some(list))
+    } else {
+        Ok(None)
+    }
+}
+
+fn frame(frames: &[PathBuf], index: usize) -> Result<Source, MetadataError> {
+    frames.get(index).cloned().map(Source::new).ok_or_else(|| {
+        MetadataError::CouldNotParse(format!(
+            "Image {index} is referenced but the container holds only {} images",
+            frames.len()
+        ))
+    })
+}
+
+fn from_solar(
+    solar: Solar,
+    location: Location,
+    frames: &[PathBuf],
+) -> Result<Metadata, MetadataError> {
+    let frames = solar
+        .images
+        .iter()
+        .map(|image| {
+            Ok(SolarFrame {
+                file: frame(frames, image.index)?,
+                position: SolarPosition::from_coordinates(image.altitude, image.azimuth),
+                transition: None,
+            })
+        })
+        .collect::<Result<Vec<SolarFrame>, MetadataError>>()?;
+    Ok(Metadata::Solar { frames, location })
+}
+
+fn from_h24(mut h24: H24, frames: &[PathBuf]) -> Result<Metadata, MetadataError> {
+    h24.images.sort_by(|a, b| a.time.total_cmp(&b.time));
+    let first = h24.images.first().ok_or(MetadataError::NoDynamicDesktop)?;
+    // Starting yesterday guarantees that the current moment lies after the start
+    let start_time = (Local::now().date().naive_local() - chrono::Duration::days(1))
+        .and_hms(0, 0, 0)
+        + chrono::Duration::seconds((first.time * SECONDS_PER_DAY) as i64);
+
+    // Each image is blended into its successor until the next image is reached, the last one wraps around
+    let mut elapsed = 0f64;
+    let mut transitions = Vec::new();
+    for (cur, next) in h24.images.iter().zip(h24.images.iter().cycle().skip(1)) {
+        let duration = if h24.images.len() == 1 {
+            SECONDS_PER_DAY
+        } else {
+            (next.time - cur.time).rem_euclid(1.0) * SECONDS_PER_DAY
+        };
+        let time_range = elapsed..elapsed + duration;
+        if cur.index == next.index {
+            transitions.push(Transition::WithoutAnimation {
+                duration,
+                time_range,
+                from: frame(frames, cur.index)?,
+            });
+        } else {
+            transitions.push(Transition::WithAnimation {
+                kind: TransitionKind::default(),
+                easing: Easing::default(),
+                duration_static: 0.0,
+                duration_transition: duration,
+                time_range,
+                from: frame(frames, cur.index)?,
+                to: frame(frames, next.index)?,
+            });
+        }
+        elapsed += duration;
+    }
+
+    Ok(Metadata::Timeline {
+        start_time,
+        image_transisitons: transitions,
+        total_duration_sec: elapsed,
+        // Times of day stay the same across changes to daylight saving time
+        clock: Clock::Wall,
+    })
+}
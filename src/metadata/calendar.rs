@@ -0,0 +1,317 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Sets of wallpapers bound to dates, e.g. to winter or to weekends. The first
+// set matching the local date is shown, a change of the set at midnight is
+// crossfaded.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc, Weekday};
+
+use super::{
+    localize, paths, Adjustment, AnimationState, Metadata, MetadataError, MetadataReader, Settings,
+};
+use crate::schema::schedule as schema;
+
+const CROSSFADE_SEC: f64 = 2.0;
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// A day given either once or repeating every year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Day {
+    Yearly { month: u32, day: u32 },
+    Date(NaiveDate),
+}
+
+#[derive(Debug, Clone)]
+pub struct CalendarSet {
+    /// Months as numbers from 1 to 12, any month if empty
+    pub months: Vec<u32>,
+    /// Any weekday if empty
+    pub weekdays: Vec<Weekday>,
+    /// Inclusive range of days
+    pub range: Option<(Day, Day)>,
+    pub wallpaper: Metadata,
+}
+
+impl CalendarSet {
+    pub fn applies(&self, date: NaiveDate) -> bool {
+        (self.months.is_empty() || self.months.contains(&date.month()))
+            && (self.weekdays.is_empty() || self.weekdays.contains(&date.weekday()))
+            && self
+                .range
+                .is_none_or(|(from, until)| in_range(date, from, until))
+    }
+}
+
+impl std::fmt::Display for CalendarSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut conditions = Vec::new();
+        if !self.months.is_empty() {
+            let months: Vec<&str> = self
+                .months
+                .iter()
+                .map(|month| MONTHS[*month as usize - 1])
+                .collect();
+            conditions.push(format!("months {}", months.join(", ")));
+        }
+        if !self.weekdays.is_empty() {
+            let weekdays: Vec<String> = self.weekdays.iter().map(|day| day.to_string()).collect();
+            conditions.push(format!("weekdays {}", weekdays.join(", ")));
+        }
+        if let Some((from, until)) = self.range {
+            conditions.push(format!("from {from} until {until}"));
+        }
+        if conditions.is_empty() {
+            f.write_str("any date")
+        } else {
+            f.write_str(&conditions.join("; "))
+        }
+    }
+}
+
+impl std::fmt::Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Day::Yearly { month, day } => write!(f, "{month:02}-{day:02}"),
+            Day::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+fn in_range(date: NaiveDate, from: Day, until: Day) -> bool {
+    match (from, until) {
+        (Day::Date(from), Day::Date(until)) => from <= date && date <= until,
+        (
+            Day::Yearly {
+                month: from_month,
+                day: from_day,
+            },
+            Day::Yearly {
+                month: until_month,
+                day: until_day,
+            },
+        ) => {
+            let day = (date.month(), date.day());
+            let (from, until) = ((from_month, from_day), (until_month, until_day));
+            if from <= until {
+                from <= day && day <= until
+            } else {
+                // The range wraps around the turn of the year
+                from <= day || day <= until
+            }
+        }
+        // Mixed forms are rejected when reading
+        _ => false,
+    }
+}
+
+fn parse_month(month: &str) -> Option<u32> {
+    let month = month.trim().to_lowercase();
+    if let Ok(number) = month.parse::<u32>() {
+        return (1..=12).contains(&number).then_some(number);
+    }
+    MONTHS
+        .iter()
+        .position(|name| month.len() >= 3 && month.starts_with(name))
+        .map(|idx| idx as u32 + 1)
+}
+
+fn parse_season(season: &str) -> Option<[u32; 3]> {
+    match season.trim().to_lowercase().as_str() {
+        "spring" => Some([3, 4, 5]),
+        "summer" => Some([6, 7, 8]),
+        "autumn" | "fall" => Some([9, 10, 11]),
+        "winter" => Some([12, 1, 2]),
+        _ => None,
+    }
+}
+
+fn parse_day(day: &str) -> Option<Day> {
+    if let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        return Some(Day::Date(date));
+    }
+    // Checked against a leap year to allow the 29th of February
+    let date = NaiveDate::parse_from_str(&format!("2000-{day}"), "%Y-%m-%d").ok()?;
+    Some(Day::Yearly {
+        month: date.month(),
+        day: date.day(),
+    })
+}
+
+fn invalid(kind: &str, value: &str, entry: &str, expected: &str) -> MetadataError {
+    MetadataError::CouldNotParse(format!(
+        "Invalid {kind} `{value}` of {entry}, expected {expected}"
+    ))
+}
+
+/// Sets are checked in order, `fallback` is shown on dates no set matches.
+/// Without a fallback the sets have to cover every date.
+pub fn read(
+    sets: Vec<schema::Set>,
+    fallback: Option<Metadata>,
+    base: &Path,
+    parents: &[PathBuf],
+    settings: &Settings,
+    issues: &mut Vec<String>,
+) -> Result<Metadata, MetadataError> {
+    let mut calendar =
+        sets.into_iter()
+            .enumerate()
+            .map(|(idx, set)| {
+                let entry = format!("set #{}", idx + 1);
+                let mut months = set
+                    .months
+                    .iter()
+                    .map(|month| {
+                        parse_month(month)
+                            .ok_or_else(|| invalid("month", month, &entry, "a name or number"))
+                    })
+                    .collect::<Result<Vec<u32>, MetadataError>>()?;
+                for season in set.seasons.iter() {
+                    months.extend(parse_season(season).ok_or_else(|| {
+                        invalid("season", season, &entry, "spring, summer, autumn or winter")
+                    })?);
+                }
+                let weekdays =
+                    set.weekdays
+                        .iter()
+                        .map(|weekday| {
+                            weekday.trim().parse::<Weekday>().map_err(|_| {
+                                invalid("weekday", weekday, &entry, "a name like `sat`")
+                            })
+                        })
+                        .collect::<Result<Vec<Weekday>, MetadataError>>()?;
+                let day = |day: &String| {
+                    parse_day(day).ok_or_else(|| invalid("day", day, &entry, "MM-DD or YYYY-MM-DD"))
+                };
+                let range = match (
+                    set.from.as_ref().map(day).transpose()?,
+                    set.until.as_ref().map(day).transpose()?,
+                ) {
+                    (None, None) => None,
+                    (Some(from @ Day::Date(_)), Some(until @ Day::Date(_)))
+                    | (Some(from @ Day::Yearly { .. }), Some(until @ Day::Yearly { .. })) => {
+                        Some((from, until))
+                    }
+                    (Some(_), Some(_)) => {
+                        return Err(MetadataError::CouldNotParse(format!(
+                            "The days of {entry} have to be given both with or both without a year"
+                        )))
+                    }
+                    _ => {
+                        return Err(MetadataError::CouldNotParse(format!(
+                            "The range of {entry} needs both `from` and `until`"
+                        )))
+                    }
+                };
+                if let Some((Day::Date(from), Day::Date(until))) = range {
+                    if until < from {
+                        issues.push(format!("The range of {entry} ends before it starts"));
+                    }
+                }
+                let mut wallpaper = MetadataReader::read_variant(
+                    paths::resolve(&set.file, base),
+                    parents,
+                    settings,
+                    issues,
+                )?;
+                wallpaper.adjust(Adjustment::parse(
+                    set.scale.as_deref(),
+                    set.align.as_deref(),
+                    &entry,
+                )?);
+                Ok(CalendarSet {
+                    months,
+                    weekdays,
+                    range,
+                    wallpaper,
+                })
+            })
+            .collect::<Result<Vec<CalendarSet>, MetadataError>>()?;
+    match fallback {
+        Some(wallpaper) => calendar.push(CalendarSet {
+            months: Vec::new(),
+            weekdays: Vec::new(),
+            range: None,
+            wallpaper,
+        }),
+        None => {
+            if let Some(date) = uncovered(&calendar) {
+                return Err(MetadataError::CouldNotParse(format!(
+                    "No set without a year applies on {}, add anchors shown on dates no set matches",
+                    date.format("%Y-%m-%d")
+                )));
+            }
+        }
+    }
+    Ok(Metadata::Calendar(calendar))
+}
+
+// Sets with dates including a year end at some point, all others repeat with
+// the weekdays of the calendar every 28 years.
+fn uncovered(sets: &[CalendarSet]) -> Option<NaiveDate> {
+    let repeating: Vec<&CalendarSet> = sets
+        .iter()
+        .filter(|set| !matches!(set.range, Some((Day::Date(_), _))))
+        .collect();
+    Local::now()
+        .date()
+        .naive_local()
+        .iter_days()
+        .take(28 * 366)
+        .find(|date| !repeating.iter().any(|set| set.applies(*date)))
+}
+
+/// The set shown on the given date, reading ensures that one matches.
+pub fn active(sets: &[CalendarSet], date: NaiveDate) -> usize {
+    sets.iter()
+        .position(|set| set.applies(date))
+        .unwrap_or(sets.len() - 1)
+}
+
+pub fn current(sets: &[CalendarSet], now: DateTime<Utc>) -> Result<AnimationState, MetadataError> {
+    let today = now.with_timezone(&Local).date().naive_local();
+    let active_today = active(sets, today);
+    let wallpaper = &sets[active_today].wallpaper;
+
+    let elapsed = (now - localize(&today.and_hms(0, 0, 0))).num_milliseconds() as f64 / 1000.0;
+    let active_yesterday = active(sets, today.pred());
+    if elapsed < CROSSFADE_SEC && active_yesterday != active_today {
+        let from = sets[active_yesterday]
+            .wallpaper
+            .current_at(now)?
+            .transition()
+            .from()
+            .clone();
+        let to = wallpaper.current_at(now)?.transition().from().clone();
+        if from != to {
+            return Ok(AnimationState::crossfade(from, to, elapsed, CROSSFADE_SEC));
+        }
+    }
+
+    let state = wallpaper.current_at(now)?;
+    let tomorrow = today.succ();
+    if active(sets, tomorrow) == active_today {
+        return Ok(state);
+    }
+    // The next set has to be shown right at midnight
+    let until = (localize(&tomorrow.and_hms(0, 0, 0)) - now).num_milliseconds() as f64 / 1000.0;
+    Ok(state.clipped(until))
+}
@@ -0,0 +1,217 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Problems in wallpaper descriptions, pointing at the place in the file where
+// they occur together with a hint on how to resolve them.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use quick_xml::events::Event;
+use quick_xml::DeError;
+use serde::de::DeserializeOwned;
+
+use super::MetadataError;
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    /// Line and column, both counted from 1
+    pub position: Option<(usize, usize)>,
+    /// The element the problem occurs in, e.g. `<static> #3`
+    pub element: Option<String>,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new<S: Into<String>>(path: &Path, message: S) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            position: None,
+            element: None,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    /// Points at the byte `offset` of `content`.
+    pub fn at(mut self, content: &str, offset: usize) -> Self {
+        self.position = Some(position(content, offset));
+        self
+    }
+
+    pub fn element(mut self, element: &Element) -> Self {
+        self.position = Some(element.position);
+        self.element = Some(element.label());
+        self.hint = hint(&element.name).map(String::from);
+        self
+    }
+
+    pub fn hint<S: Into<String>>(mut self, hint: S) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.to_string_lossy())?;
+        if let Some((line, column)) = self.position {
+            write!(f, ":{line}:{column}")?;
+        }
+        if let Some(element) = &self.element {
+            write!(f, ": {element}")?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(hint) = &self.hint {
+            write!(f, "\n  hint: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Diagnostic> for MetadataError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        MetadataError::Invalid(Box::new(diagnostic))
+    }
+}
+
+/// A child of the root element of an XML description.
+#[derive(Debug)]
+pub struct Element {
+    pub name: String,
+    index: usize,
+    position: (usize, usize),
+    range: Range<usize>,
+}
+
+impl Element {
+    // Counted like the entries of issues, see `super::entry`
+    fn label(&self) -> String {
+        format!("<{}> #{}", self.name, self.index + 1)
+    }
+}
+
+fn hint(element: &str) -> Option<&'static str> {
+    match element {
+        "starttime" => Some("A <starttime> needs a <year>, <month>, <day>, <hour>, <minute> and <second>."),
+        "static" => Some("A <static> block needs a <duration> in seconds and a <file>."),
+        "transition" => Some("A <transition> needs a <duration> in seconds, a <from> and a <to> file, and optionally a type attribute."),
+        "solar" => Some("A <solar> image needs a <file> and either a <phase> or an <elevation> together with an <azimuth>."),
+        "wallpaper" => Some("A <wallpaper> needs at least a <filename>."),
+        _ => None,
+    }
+}
+
+/// The children of the root element, in order.
+pub fn elements(path: &Path, content: &str) -> Result<Vec<Element>, Diagnostic> {
+    let mut reader = quick_xml::Reader::from_str(content);
+    let mut elements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut name = String::new();
+    loop {
+        let before = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| {
+            Diagnostic::new(path, e.to_string()).at(content, reader.error_position() as usize)
+        })?;
+        match event {
+            Event::Start(element) => {
+                depth += 1;
+                if depth == 2 {
+                    start = before;
+                    name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+                }
+            }
+            Event::Empty(element) if depth == 1 => {
+                let name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+                elements.push(located(
+                    content,
+                    name,
+                    elements.len(),
+                    before..reader.buffer_position() as usize,
+                ));
+            }
+            Event::End(_) => {
+                if depth == 2 {
+                    let range = start..reader.buffer_position() as usize;
+                    elements.push(located(content, name.clone(), elements.len(), range));
+                }
+                depth -= 1;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(elements)
+}
+
+fn located(content: &str, name: String, index: usize, range: Range<usize>) -> Element {
+    Element {
+        name,
+        index,
+        position: position(content, range.start),
+        range,
+    }
+}
+
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Parses an XML description of type `T`, whose children named `child` are of type `C`.
+///
+/// If the description cannot be parsed, each child is parsed on its own to
+/// find the one causing the problem.
+pub fn parse_xml<T: DeserializeOwned, C: DeserializeOwned>(
+    path: &Path,
+    content: &str,
+    child: Option<&str>,
+) -> Result<(T, Vec<Element>), MetadataError> {
+    let elements = elements(path, content)?;
+    let error = match quick_xml::de::from_str::<T>(content) {
+        Ok(parsed) => return Ok((parsed, elements)),
+        Err(e) => e,
+    };
+    for element in elements
+        .iter()
+        .filter(|element| child.is_none_or(|child| element.name == child))
+    {
+        if let Err(e) = quick_xml::de::from_str::<C>(&content[element.range.clone()]) {
+            return Err(Diagnostic::new(path, message(e)).element(element).into());
+        }
+    }
+    Err(Diagnostic::new(path, message(error)).into())
+}
+
+// The deserializer reports some problems in terms of its parser events
+fn message(error: DeError) -> String {
+    match error {
+        // Attributes are fields with an `@` prefix
+        DeError::Custom(message) => message.replace("field `@", "attribute `"),
+        DeError::UnexpectedStart(name) => {
+            format!("unexpected element <{}>", String::from_utf8_lossy(&name))
+        }
+        DeError::UnexpectedEof => "unexpected end of file".into(),
+        e => e.to_string(),
+    }
+}
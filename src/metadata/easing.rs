@@ -0,0 +1,66 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::MetadataError;
+
+/// Course of the progress over the duration of a transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    #[default]
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+}
+
+impl Easing {
+    pub fn from_name(name: &str) -> Result<Self, MetadataError> {
+        Ok(match name.trim().to_lowercase().as_str() {
+            "linear" => Easing::Linear,
+            "quad-in" => Easing::QuadIn,
+            "quad-out" => Easing::QuadOut,
+            "quad-inout" => Easing::QuadInOut,
+            "cubic-in" => Easing::CubicIn,
+            "cubic-out" => Easing::CubicOut,
+            "cubic-inout" => Easing::CubicInOut,
+            "sine-in" => Easing::SineIn,
+            "sine-out" => Easing::SineOut,
+            "sine-inout" => Easing::SineInOut,
+            _ => return Err(MetadataError::InvalidEasing(name.into())),
+        })
+    }
+
+    pub fn apply(&self, progress: f32) -> f32 {
+        match self {
+            Easing::Linear => ezing::linear(progress),
+            Easing::QuadIn => ezing::quad_in(progress),
+            Easing::QuadOut => ezing::quad_out(progress),
+            Easing::QuadInOut => ezing::quad_inout(progress),
+            Easing::CubicIn => ezing::cubic_in(progress),
+            Easing::CubicOut => ezing::cubic_out(progress),
+            Easing::CubicInOut => ezing::cubic_inout(progress),
+            Easing::SineIn => ezing::sine_in(progress),
+            Easing::SineOut => ezing::sine_out(progress),
+            Easing::SineInOut => ezing::sine_inout(progress),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Paths in wallpaper descriptions are written by hand and shipped in packs,
+// they are interpreted like a shell would relative to the description file.
+
+use std::path::{Path, PathBuf};
+
+use lazy_regex::{regex, Captures};
+use log::warn;
+
+// Defaults of the XDG base directory specification for variables which are commonly unset
+fn variable(name: &str) -> Option<String> {
+    std::env::var(name).ok().or_else(|| {
+        let home = std::env::var("HOME").ok()?;
+        match name {
+            "XDG_DATA_HOME" => Some(format!("{home}/.local/share")),
+            "XDG_CONFIG_HOME" => Some(format!("{home}/.config")),
+            "XDG_CACHE_HOME" => Some(format!("{home}/.cache")),
+            _ => None,
+        }
+    })
+}
+
+/// Expand `~` and environment variables, relative paths are taken relative to `base`.
+pub fn resolve(path: &Path, base: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    let expanded = regex!(r"\$(?:\{(\w+)\}|(\w+))").replace_all(&raw, |caps: &Captures| {
+        let name = caps
+            .get(1)
+            .or_else(|| caps.get(2))
+            .expect("One group matches")
+            .as_str();
+        variable(name).unwrap_or_else(|| {
+            warn!(
+                "Environment variable `{name}` used in {:?} is not set",
+                path
+            );
+            caps[0].to_string()
+        })
+    });
+    let expanded = match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match variable("HOME") {
+            Some(home) => format!("{home}{rest}"),
+            None => expanded.into_owned(),
+        },
+        _ => expanded.into_owned(),
+    };
+    base.join(expanded)
+}
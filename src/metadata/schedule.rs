@@ -0,0 +1,142 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Schedules list images by the time of day, they are converted to a timeline
+// repeating every day.
+
+use std::path::Path;
+
+use chrono::{Duration, Local, NaiveTime};
+
+use super::{
+    transition_kind, Adjustment, Clock, Easing, Metadata, MetadataError, Source, Transition,
+};
+use crate::opengl::transition::TransitionKind;
+use crate::schema::schedule as schema;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+struct Anchor {
+    time: NaiveTime,
+    file: Source,
+    transition: f64,
+    easing: Easing,
+    kind: TransitionKind,
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()
+}
+
+pub fn read(
+    anchors: Vec<schema::Anchor>,
+    base: &Path,
+    issues: &mut Vec<String>,
+) -> Result<Metadata, MetadataError> {
+    let mut anchors = anchors
+        .into_iter()
+        .enumerate()
+        .map(|(idx, anchor)| {
+            let entry = format!("anchor #{}", idx + 1);
+            let time = parse_time(&anchor.time).ok_or_else(|| {
+                MetadataError::CouldNotParse(format!(
+                    "Invalid time `{}` of {entry}, expected HH:MM or HH:MM:SS",
+                    anchor.time
+                ))
+            })?;
+            let transition = anchor.transition.unwrap_or(0.0);
+            if transition < 0.0 {
+                issues.push(format!(
+                    "The transition of {entry} is negative: {transition}s"
+                ));
+            }
+            Ok(Anchor {
+                time,
+                file: Source::resolve(&anchor.file, base, &entry)?.adjusted(Adjustment::parse(
+                    anchor.scale.as_deref(),
+                    anchor.align.as_deref(),
+                    &entry,
+                )?),
+                transition: transition.max(0.0),
+                easing: anchor
+                    .easing
+                    .map(|easing| Easing::from_name(&easing))
+                    .transpose()?
+                    .unwrap_or_default(),
+                kind: anchor
+                    .kind
+                    .map(|kind| transition_kind(&kind, &entry, issues))
+                    .unwrap_or_default(),
+            })
+        })
+        .collect::<Result<Vec<Anchor>, MetadataError>>()?;
+    anchors.sort_by_key(|anchor| anchor.time);
+    if anchors.windows(2).any(|pair| pair[0].time == pair[1].time) {
+        return Err(MetadataError::CouldNotParse(
+            "Multiple anchors share the same time".into(),
+        ));
+    }
+    let first = anchors
+        .first()
+        .ok_or_else(|| MetadataError::CouldNotParse("The schedule contains no anchor".into()))?;
+    // Starting yesterday guarantees that the current moment lies after the start
+    let start_time = (Local::now().date().naive_local() - Duration::days(1)).and_time(first.time);
+
+    // Each image is shown until it blends into its successor, the last one wraps around to the first
+    let mut elapsed = 0f64;
+    let mut transitions = Vec::new();
+    for (cur, next) in anchors.iter().zip(anchors.iter().cycle().skip(1)) {
+        // A single anchor follows itself after a whole day
+        let gap = ((next.time - cur.time).num_seconds() as f64).rem_euclid(SECONDS_PER_DAY);
+        let gap = if gap > 0.0 { gap } else { SECONDS_PER_DAY };
+        if cur.transition > gap {
+            issues.push(format!(
+                "The transition after {} lasts {}s but the next anchor follows after {gap}s",
+                cur.time, cur.transition
+            ));
+        }
+        let duration_transition = cur.transition.min(gap);
+        let time_range = elapsed..elapsed + gap;
+        if duration_transition > 0.0 && cur.file != next.file {
+            transitions.push(Transition::WithAnimation {
+                kind: cur.kind,
+                easing: cur.easing,
+                duration_static: gap - duration_transition,
+                duration_transition,
+                time_range,
+                from: cur.file.clone(),
+                to: next.file.clone(),
+            });
+        } else {
+            transitions.push(Transition::WithoutAnimation {
+                duration: gap,
+                time_range,
+                from: cur.file.clone(),
+            });
+        }
+        elapsed += gap;
+    }
+
+    Ok(Metadata::Timeline {
+        start_time,
+        image_transisitons: transitions,
+        total_duration_sec: elapsed,
+        // Times of day stay the same across changes to daylight saving time
+        clock: Clock::Wall,
+    })
+}
@@ -0,0 +1,289 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Slideshows cycle through the images of a directory. The directory is scanned
+// again whenever the next image is due, so that changes are picked up at runtime.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, warn};
+
+use super::{Adjustment, AnimationState, Easing, MetadataError, Source, Transition};
+use crate::opengl::transition::TransitionKind;
+
+const CROSSFADE_SEC: f64 = 2.0;
+
+/// Order in which the images of a slideshow are shown.
+#[derive(PartialEq, Debug, Clone, Copy, Hash, Eq)]
+pub enum Order {
+    Name,
+    /// Oldest modification first.
+    Mtime,
+    /// Random order, every image is shown once before any is repeated.
+    Shuffle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlideshowSettings {
+    /// Seconds each image is shown, including the crossfade into the next one.
+    pub interval: f64,
+    pub order: Order,
+    pub recursive: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Slideshow {
+    directory: PathBuf,
+    settings: SlideshowSettings,
+    images: Vec<PathBuf>,
+    start: DateTime<Utc>,
+    /// Position of the image shown at `start`.
+    first: usize,
+    seed: u64,
+    adjustment: Adjustment,
+    /// Images which could not be loaded, they are left out of all following scans.
+    skipped: HashSet<PathBuf>,
+}
+
+impl Slideshow {
+    pub fn new(directory: PathBuf, settings: SlideshowSettings) -> Result<Self, MetadataError> {
+        let images = scan(&directory, &settings)?;
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+        Ok(Self {
+            directory,
+            settings,
+            images,
+            start: Utc::now(),
+            first: 0,
+            seed,
+            adjustment: Adjustment::default(),
+            skipped: HashSet::new(),
+        })
+    }
+
+    pub fn images(&self) -> &[PathBuf] {
+        &self.images
+    }
+
+    pub fn settings(&self) -> &SlideshowSettings {
+        &self.settings
+    }
+
+    /// Apply the options of an entry to all images, see `Metadata::adjust`.
+    pub fn adjust(&mut self, adjustment: Adjustment) {
+        self.adjustment = self.adjustment.or(adjustment);
+    }
+
+    /// Update the images from the directory, the currently shown image stays in place.
+    pub fn rescan(&mut self, now: DateTime<Utc>) {
+        let mut images = match scan(&self.directory, &self.settings) {
+            Ok(images) => images,
+            Err(e) => {
+                warn!("Could not rescan slideshow, keeping the known images: {e}");
+                return;
+            }
+        };
+        images.retain(|image| !self.skipped.contains(image));
+        // An unmounted or emptied directory is more likely a hiccup than the end of the slideshow
+        if images.is_empty() {
+            warn!(
+                "Slideshow directory {:?} contains no images, keeping the known images",
+                self.directory
+            );
+            return;
+        }
+        if images == self.images {
+            return;
+        }
+        debug!(
+            "Slideshow changed {{ directory: {:?}, images: {} }}",
+            self.directory,
+            images.len()
+        );
+        self.replace(images, now);
+    }
+
+    /// Leave out an image which cannot be loaded and continue with the next one.
+    /// Returns whether the image belongs to this slideshow.
+    pub fn skip(&mut self, path: &Path, now: DateTime<Utc>) -> bool {
+        if !self.images.iter().any(|image| image == path) {
+            return false;
+        }
+        self.skipped.insert(path.to_path_buf());
+        let images = self
+            .images
+            .iter()
+            .filter(|image| *image != path)
+            .cloned()
+            .collect();
+        self.replace(images, now);
+        true
+    }
+
+    // The current image keeps being shown, if it is gone the slideshow continues with the next remaining one
+    fn replace(&mut self, images: Vec<PathBuf>, now: DateTime<Utc>) {
+        let (slide, progress) = self.position(now);
+        let upcoming: Vec<PathBuf> = (0..self.images.len())
+            .filter_map(|offset| self.image(self.first + slide + offset).cloned())
+            .collect();
+        self.images = images;
+        self.seed = self.seed.wrapping_add(1);
+        self.start = now - Duration::milliseconds((progress * 1000.0) as i64);
+        let order = self.order(0);
+        self.first = upcoming
+            .iter()
+            .find_map(|path| order.iter().position(|idx| self.images[*idx] == *path))
+            .unwrap_or(0);
+    }
+
+    pub fn current(&self, now: DateTime<Utc>) -> Result<AnimationState, MetadataError> {
+        let (slide, progress) = self.position(now);
+        let cur = self
+            .image(self.first + slide)
+            .ok_or(MetadataError::NoImages)?;
+        let next = self
+            .image(self.first + slide + 1)
+            .ok_or(MetadataError::NoImages)?;
+        let interval = self.settings.interval;
+        let time_range = 0.0..interval;
+        if cur == next {
+            return Ok(AnimationState::Static(
+                progress,
+                Transition::WithoutAnimation {
+                    duration: interval,
+                    time_range,
+                    from: Source::new(cur.clone()).adjusted(self.adjustment),
+                },
+            ));
+        }
+        let duration_transition = CROSSFADE_SEC.min(interval);
+        let duration_static = interval - duration_transition;
+        let transition = Transition::WithAnimation {
+            kind: TransitionKind::default(),
+            easing: Easing::default(),
+            duration_static,
+            duration_transition,
+            time_range,
+            from: Source::new(cur.clone()).adjusted(self.adjustment),
+            to: Source::new(next.clone()).adjusted(self.adjustment),
+        };
+        Ok(if progress < duration_static {
+            AnimationState::Static(progress, transition)
+        } else {
+            AnimationState::Transition(progress - duration_static, transition)
+        })
+    }
+
+    // Number of the current slide since the start and the seconds it has been shown
+    fn position(&self, now: DateTime<Utc>) -> (usize, f64) {
+        let elapsed = ((now - self.start).num_milliseconds() as f64 / 1000.0).max(0.0);
+        let slide = (elapsed / self.settings.interval).floor();
+        (slide as usize, elapsed - slide * self.settings.interval)
+    }
+
+    // The n-th image shown since the start, each cycle through all images has its own order
+    fn image(&self, n: usize) -> Option<&PathBuf> {
+        let count = self.images.len();
+        if count == 0 {
+            return None;
+        }
+        let order = self.order(n / count);
+        Some(&self.images[order[n % count]])
+    }
+
+    fn order(&self, cycle: usize) -> Vec<usize> {
+        let mut order = self.shuffled(cycle);
+        // The last image of the previous cycle must not be repeated right away, swapping the
+        // first two images keeps the last one of each cycle unchanged
+        if cycle > 0 && order.len() > 2 && self.shuffled(cycle - 1).last() == order.first() {
+            order.swap(0, 1);
+        }
+        order
+    }
+
+    fn shuffled(&self, cycle: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        // Two images alternate anyway, shuffling them could show one twice in a row
+        if self.settings.order == Order::Shuffle && order.len() > 2 {
+            let mut rng = SplitMix(self.seed ^ (cycle as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            for idx in (1..order.len()).rev() {
+                order.swap(idx, (rng.next() % (idx as u64 + 1)) as usize);
+            }
+        }
+        order
+    }
+}
+
+// Small deterministic generator, the same seed and cycle always give the same order
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// The images in the directory, ordered by name or modification time.
+pub fn scan(directory: &Path, settings: &SlideshowSettings) -> Result<Vec<PathBuf>, MetadataError> {
+    let mut images = Vec::new();
+    collect(directory, settings.recursive, &mut images)?;
+    match settings.order {
+        Order::Name | Order::Shuffle => images.sort(),
+        Order::Mtime => images.sort_by_key(|path| {
+            (
+                std::fs::metadata(path)
+                    .and_then(|meta| meta.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH),
+                path.clone(),
+            )
+        }),
+    }
+    Ok(images)
+}
+
+fn collect(
+    directory: &Path,
+    recursive: bool,
+    images: &mut Vec<PathBuf>,
+) -> Result<(), MetadataError> {
+    let entries = std::fs::read_dir(directory)
+        .map_err(|e| MetadataError::CouldNotOpen(format!("{:?}: {}", directory, e)))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Hidden files are usually thumbnails or metadata of other tools
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if recursive {
+                collect(&path, recursive, images)?;
+            }
+        } else if crate::image::is_supported(&path) {
+            images.push(path);
+        }
+    }
+    Ok(())
+}
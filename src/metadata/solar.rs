@@ -0,0 +1,210 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Offline computation of the sun position. The formulas are the low precision
+// approximations published by the U.S. Naval Observatory, which are accurate to
+// about a minute for the next decades. This is more than enough to decide which
+// frame of a wallpaper should be shown.
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+use super::{AnimationState, Easing, MetadataError, Source, Transition};
+use crate::opengl::transition::TransitionKind;
+
+// Elevation of the sun's center at the moment its upper limb touches the horizon, accounting for refraction
+const HORIZON: f64 = -0.833;
+// Civil twilight begins and ends at this elevation
+const CIVIL_TWILIGHT: f64 = -6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// The position in the daily course of the sun at which a frame is shown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolarPosition {
+    Midnight,
+    Noon,
+    Elevation { degrees: f64, rising: bool },
+}
+
+impl SolarPosition {
+    pub fn from_phase(phase: &str) -> Result<Self, MetadataError> {
+        Ok(match phase.trim().to_lowercase().as_str() {
+            "night" | "midnight" => SolarPosition::Midnight,
+            "dawn" => SolarPosition::Elevation {
+                degrees: CIVIL_TWILIGHT,
+                rising: true,
+            },
+            "sunrise" => SolarPosition::Elevation {
+                degrees: HORIZON,
+                rising: true,
+            },
+            "noon" | "day" => SolarPosition::Noon,
+            "sunset" => SolarPosition::Elevation {
+                degrees: HORIZON,
+                rising: false,
+            },
+            "dusk" => SolarPosition::Elevation {
+                degrees: CIVIL_TWILIGHT,
+                rising: false,
+            },
+            _ => return Err(MetadataError::InvalidSolarPosition(phase.into())),
+        })
+    }
+
+    // The sun is in the eastern half of the sky before noon, this holds on both hemispheres
+    pub fn from_coordinates(elevation: f64, azimuth: f64) -> Self {
+        SolarPosition::Elevation {
+            degrees: elevation,
+            rising: azimuth.rem_euclid(360.0) < 180.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SolarFrame {
+    pub file: Source,
+    pub position: SolarPosition,
+    /// Length of the blend into the next frame in seconds, `None` blends over the complete time between both frames.
+    pub transition: Option<f64>,
+}
+
+struct Anchor<'a> {
+    time: DateTime<Utc>,
+    frame: &'a SolarFrame,
+}
+
+/// Sun declination in degrees and the equation of time in hours for the given moment.
+fn sun_coordinates(time: &DateTime<Utc>) -> (f64, f64) {
+    // Days since the epoch J2000.0
+    let days = (time.timestamp() as f64 - 946_728_000.0) / 86_400.0;
+    let mean_anomaly = (357.529 + 0.985_600_28 * days).to_radians();
+    let mean_longitude = (280.459 + 0.985_647_36 * days).rem_euclid(360.0);
+    let ecliptic_longitude =
+        (mean_longitude + 1.915 * mean_anomaly.sin() + 0.020 * (2.0 * mean_anomaly).sin())
+            .to_radians();
+    let obliquity = (23.439 - 0.000_000_36 * days).to_radians();
+
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin())
+        .atan2(ecliptic_longitude.cos())
+        .to_degrees()
+        .rem_euclid(360.0);
+    let declination = (obliquity.sin() * ecliptic_longitude.sin())
+        .asin()
+        .to_degrees();
+    // Difference of mean and apparent solar time, normalized to -12h..12h
+    let equation_of_time =
+        ((mean_longitude - right_ascension + 180.0).rem_euclid(360.0) - 180.0) / 15.0;
+    (declination, equation_of_time)
+}
+
+/// Moment at which the sun reaches its highest point on the given day.
+fn solar_noon(date: NaiveDate, location: &Location) -> DateTime<Utc> {
+    let approximate = Utc.from_utc_datetime(&date.and_hms(12, 0, 0))
+        - Duration::seconds((location.longitude / 15.0 * 3600.0) as i64);
+    let (_, equation_of_time) = sun_coordinates(&approximate);
+    approximate - Duration::seconds((equation_of_time * 3600.0) as i64)
+}
+
+/// Moment at which the sun is at the given position on the given day.
+fn anchor_time(date: NaiveDate, location: &Location, position: &SolarPosition) -> DateTime<Utc> {
+    let noon = solar_noon(date, location);
+    let (degrees, rising) = match position {
+        SolarPosition::Noon => return noon,
+        SolarPosition::Midnight => return noon + Duration::hours(12),
+        SolarPosition::Elevation { degrees, rising } => (degrees, rising),
+    };
+    let (declination, _) = sun_coordinates(&noon);
+    let (lat, decl) = (location.latitude.to_radians(), declination.to_radians());
+    // Elevations which are not reached on this day are clamped to noon or midnight respectively
+    let cos_hour_angle = ((degrees.to_radians().sin() - lat.sin() * decl.sin())
+        / (lat.cos() * decl.cos()))
+    .clamp(-1.0, 1.0);
+    let offset = Duration::seconds((cos_hour_angle.acos().to_degrees() / 15.0 * 3600.0) as i64);
+    if *rising {
+        noon - offset
+    } else {
+        noon + offset
+    }
+}
+
+/// Moments at which the frames are reached on the given day, in chronological order.
+pub fn schedule<'a>(
+    frames: &'a [SolarFrame],
+    location: &Location,
+    date: NaiveDate,
+) -> Vec<(DateTime<Utc>, &'a SolarFrame)> {
+    let mut anchors: Vec<(DateTime<Utc>, &SolarFrame)> = frames
+        .iter()
+        .map(|frame| (anchor_time(date, location, &frame.position), frame))
+        .collect();
+    anchors.sort_by_key(|(time, _)| *time);
+    anchors
+}
+
+pub fn current(
+    frames: &[SolarFrame],
+    location: &Location,
+    now: DateTime<Utc>,
+) -> Result<AnimationState, MetadataError> {
+    // Anchors of the surrounding days are required to bridge midnight and polar edge cases
+    let today = now.naive_utc().date();
+    let mut anchors: Vec<Anchor> = [today.pred(), today, today.succ()]
+        .iter()
+        .flat_map(|date| schedule(frames, location, *date))
+        .map(|(time, frame)| Anchor { time, frame })
+        .collect();
+    anchors.sort_by_key(|anchor| anchor.time);
+
+    let (cur, next) = anchors
+        .windows(2)
+        .map(|pair| (&pair[0], &pair[1]))
+        .find(|(cur, next)| cur.time <= now && now < next.time)
+        .ok_or(MetadataError::CurrentFrame)?;
+
+    let length = (next.time - cur.time).num_milliseconds() as f64 / 1000.0;
+    let progress = (now - cur.time).num_milliseconds() as f64 / 1000.0;
+    if cur.frame.file == next.frame.file {
+        return Ok(AnimationState::Static(
+            progress,
+            Transition::WithoutAnimation {
+                duration: length,
+                time_range: 0.0..length,
+                from: cur.frame.file.clone(),
+            },
+        ));
+    }
+
+    let duration_transition = cur.frame.transition.unwrap_or(length).clamp(0.0, length);
+    let duration_static = length - duration_transition;
+    let transition = Transition::WithAnimation {
+        kind: TransitionKind::default(),
+        easing: Easing::default(),
+        duration_static,
+        duration_transition,
+        time_range: 0.0..length,
+        from: cur.frame.file.clone(),
+        to: next.frame.file.clone(),
+    };
+    Ok(if progress < duration_static {
+        AnimationState::Static(progress, transition)
+    } else {
+        AnimationState::Transition(progress - duration_static, transition)
+    })
+}
@@ -0,0 +1,151 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Wallpapers with a light and a dark variant. Which one is shown depends on a
+// preference, switching between both is done with a short crossfade.
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+use log::warn;
+
+use super::{AnimationState, Metadata, MetadataError};
+use crate::watchdog::appearance::system_appearance;
+
+const CROSSFADE_SEC: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+/// Where the choice between the light and the dark variant comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preference {
+    Fixed(Appearance),
+    /// The dark variant is shown from `dark_from` until `light_from` in local time.
+    Schedule {
+        light_from: NaiveTime,
+        dark_from: NaiveTime,
+    },
+    /// Follows the color scheme of the desktop, see [system_appearance].
+    System,
+}
+
+/// The most recent change of the shown variant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Switch {
+    pub appearance: Appearance,
+    pub at: DateTime<Utc>,
+}
+
+impl Switch {
+    /// A switch long enough ago to not be animated anymore.
+    pub fn settled(appearance: Appearance) -> Self {
+        Self {
+            appearance,
+            at: Utc.timestamp(0, 0),
+        }
+    }
+}
+
+pub fn initial_switch(preference: &Preference) -> Switch {
+    match preference {
+        Preference::Fixed(appearance) => Switch::settled(*appearance),
+        Preference::System => Switch::settled(system_appearance().unwrap_or_else(|| {
+            warn!("Could not determine the system color scheme. Showing the light variant.");
+            Appearance::Light
+        })),
+        // Computed from the time of day whenever needed
+        Preference::Schedule { .. } => Switch::settled(Appearance::Light),
+    }
+}
+
+/// Last switch of the schedule before `now` and the next one after it.
+fn scheduled(
+    light_from: &NaiveTime,
+    dark_from: &NaiveTime,
+    now: DateTime<Utc>,
+) -> (Switch, DateTime<Utc>) {
+    let today = now.with_timezone(&Local).date().naive_local();
+    let mut switches: Vec<Switch> = [today.pred(), today, today.succ()]
+        .iter()
+        .flat_map(|date| {
+            [
+                (Appearance::Light, date.and_time(*light_from)),
+                (Appearance::Dark, date.and_time(*dark_from)),
+            ]
+        })
+        .filter_map(|(appearance, time)| {
+            // Times skipped by a change to daylight saving time are dropped
+            Local
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|at| Switch {
+                    appearance,
+                    at: at.with_timezone(&Utc),
+                })
+        })
+        .collect();
+    switches.sort_by_key(|switch| switch.at);
+    let next = switches
+        .iter()
+        .position(|switch| switch.at > now)
+        .expect("The schedule of tomorrow lies in the future");
+    let last = switches[..next]
+        .last()
+        .copied()
+        .unwrap_or_else(|| Switch::settled(Appearance::Light));
+    (last, switches[next].at)
+}
+
+pub fn current(
+    light: &Metadata,
+    dark: &Metadata,
+    preference: &Preference,
+    switch: &Switch,
+    now: DateTime<Utc>,
+) -> Result<AnimationState, MetadataError> {
+    let (switch, next) = match preference {
+        Preference::Schedule {
+            light_from,
+            dark_from,
+        } => {
+            let (switch, next) = scheduled(light_from, dark_from, now);
+            (switch, Some(next))
+        }
+        _ => (*switch, None),
+    };
+    let (active, inactive) = match switch.appearance {
+        Appearance::Light => (light, dark),
+        Appearance::Dark => (dark, light),
+    };
+
+    let elapsed = (now - switch.at).num_milliseconds() as f64 / 1000.0;
+    if elapsed < CROSSFADE_SEC {
+        let from = inactive.current_at(now)?.transition().from().clone();
+        let to = active.current_at(now)?.transition().from().clone();
+        if from != to {
+            return Ok(AnimationState::crossfade(from, to, elapsed, CROSSFADE_SEC));
+        }
+    }
+
+    let state = active.current_at(now)?;
+    // The shown image has to be refreshed at the next switch at the latest
+    match next {
+        Some(next) => Ok(state.clipped((next - now).num_milliseconds() as f64 / 1000.0)),
+        None => Ok(state),
+    }
+}
@@ -15,5 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 pub mod context;
+pub mod dither;
 pub mod error;
 pub mod shader;
+pub mod transition;
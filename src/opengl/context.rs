@@ -14,18 +14,200 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::dither::{self, Dither};
 use super::error::check_error;
 use super::shader::Shader;
+use super::transition::TransitionKind;
+use crate::image::scaling::{self, Background, Filter, Frame, Placement, Scaling};
+use crate::image::{Original, Timing};
+use crate::outputs::ScaledMode;
+use enkei::fill::{Fill, Shape};
 use log::debug;
 
+// Units 0 to 3 hold the images, the noise and the backdrop sampled by the
+// shaders. Shared images are uploaded on a unit of their own, so that these
+// stay bound in whichever context is current.
+const UPLOAD_UNIT: u32 = gl::TEXTURE4;
+
 #[derive(Debug)]
 pub struct Context {
     _vao: u32,
     _vbo: u32,
     _ebo: u32,
-    tex_from: u32,
-    tex_to: u32,
-    shader_program: Program,
+    // One texture per frame, still images only use the first one
+    tex_from: Vec<u32>,
+    tex_to: Vec<u32>,
+    // Shown below transparent parts of the images
+    tex_backdrop: u32,
+    _tex_noise: Option<u32>,
+    // Images shared between outputs replace the textures above, they are
+    // placed on the output by the shader
+    shared: [Option<Rc<SharedImage>>; 2],
+    layouts: [Layout; 2],
+    samplers: [u32; 2],
+    resolution: [f32; 2],
+    // One program per transition, they all share the vertex shader inputs and textures
+    programs: HashMap<TransitionKind, Program>,
+    fill: Program,
+}
+
+/// An image uploaded once at its own size, the contexts of all outputs share it.
+#[derive(Debug)]
+pub struct SharedImage {
+    // One texture per frame, all with mipmaps
+    textures: Vec<u32>,
+    width: u32,
+    height: u32,
+    pub timing: Timing,
+    dominant: [u8; 3],
+}
+
+impl SharedImage {
+    /// Upload the frames of the image, `None` if it is larger than textures
+    /// may be on this GPU.
+    pub fn new(original: &Original) -> Option<Self> {
+        let mut max_size = 0i32;
+        unsafe { gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut max_size) };
+        if original.width.max(original.height) > max_size.max(0) as u32 {
+            debug!(
+                "Image exceeds texture size {{ size: {}x{}, max: {} }}",
+                original.width, original.height, max_size
+            );
+            return None;
+        }
+        let mut textures = vec![0u32; original.frames.len()];
+        unsafe {
+            gl::ActiveTexture(UPLOAD_UNIT);
+            gl::GenTextures(textures.len() as i32, textures.as_mut_ptr());
+            for (frame, tex_id) in original.frames.iter().zip(textures.iter()) {
+                Context::set_image(
+                    frame,
+                    original.width as i32,
+                    original.height as i32,
+                    *tex_id,
+                );
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+                check_error("mipmap generation");
+            }
+        }
+        Some(Self {
+            textures,
+            width: original.width,
+            height: original.height,
+            timing: original.timing.clone(),
+            dominant: original.dominant,
+        })
+    }
+}
+
+impl Drop for SharedImage {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteTextures(self.textures.len() as i32, self.textures.as_ptr()) };
+    }
+}
+
+/// Where the shader finds a shared image on the output, all positions and
+/// sizes are relative to the output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Layout {
+    // Position and size of the image
+    area: [f32; 4],
+    // Position and size of the image covering the output, blurred
+    // backgrounds are sampled from it
+    cover: [f32; 4],
+    // Mipmap levels of the image and of the blurred background
+    lod: [f32; 2],
+    // 1 repeats the image, 2 mirrors it and 3 blurs it around itself
+    extend: i32,
+    // Shown around the image, premultiplied. Blurred backgrounds are
+    // multiplied with it instead
+    background: [f32; 4],
+}
+
+impl Default for Layout {
+    // Textures placed by the CPU already span the output
+    fn default() -> Self {
+        Self {
+            area: [0.0, 0.0, 1.0, 1.0],
+            cover: [0.0, 0.0, 1.0, 1.0],
+            lod: [0.0, 0.0],
+            extend: 0,
+            background: [0.0; 4],
+        }
+    }
+}
+
+impl Layout {
+    fn new(image: &SharedImage, placement: &Placement, geometry: &ScaledMode) -> Self {
+        let output = [geometry.width.max(1) as f32, geometry.height.max(1) as f32];
+        // The position and size of the image, and the level at which one texel
+        // of the mipmap is about one pixel of the output
+        let locate = |placement: &Placement| {
+            let (width, height) = placement.size(image.width, image.height, geometry);
+            let (x, y) = placement.alignment.offset(width, height, geometry);
+            let texels = f32::max(
+                image.width as f32 / width as f32,
+                image.height as f32 / height as f32,
+            );
+            (
+                [
+                    x as f32 / output[0],
+                    y as f32 / output[1],
+                    width as f32 / output[0],
+                    height as f32 / output[1],
+                ],
+                texels.log2().max(0.0),
+            )
+        };
+        let (area, lod) = locate(placement);
+        let (cover, cover_lod) = locate(&Placement::default());
+        let (width, height) = placement.size(image.width, image.height, geometry);
+        let covered = placement.covers(width, height, geometry);
+        let extend = match placement.background {
+            _ if placement.scaling == Scaling::Tile => 1,
+            _ if covered => 0,
+            Background::Mirror => 2,
+            Background::Blur => 3,
+            _ => 0,
+        };
+        let opaque = |[red, green, blue]: [u8; 3]| {
+            [red, green, blue, u8::MAX].map(|channel| channel as f32 / 255.0)
+        };
+        let background = match placement.background {
+            Background::Color(color) => opaque(color),
+            Background::Dominant => opaque(image.dominant),
+            Background::Blur => [scaling::BLUR_BRIGHTNESS; 4],
+            Background::Mirror | Background::Transparent => [0.0; 4],
+        };
+        Self {
+            area,
+            cover,
+            lod: [lod, cover_lod + (scaling::BLUR_REDUCTION as f32).log2()],
+            extend,
+            background,
+        }
+    }
+
+    // Tiled and mirrored images are extended by the sampler
+    unsafe fn configure(&self, sampler: u32, filter: Filter) {
+        let wrap = match self.extend {
+            1 => gl::REPEAT,
+            2 => gl::MIRRORED_REPEAT,
+            _ => gl::CLAMP_TO_EDGE,
+        };
+        let min_filter = match filter {
+            Filter::Fast => gl::LINEAR_MIPMAP_NEAREST,
+            Filter::Good | Filter::Best => gl::LINEAR_MIPMAP_LINEAR,
+        };
+        gl::SamplerParameteri(sampler, gl::TEXTURE_WRAP_S, wrap as i32);
+        gl::SamplerParameteri(sampler, gl::TEXTURE_WRAP_T, wrap as i32);
+        gl::SamplerParameteri(sampler, gl::TEXTURE_MIN_FILTER, min_filter as i32);
+        gl::SamplerParameteri(sampler, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        check_error("sampler parameters");
+    }
 }
 
 #[derive(Debug)]
@@ -36,9 +218,36 @@ pub struct Program {
 }
 
 impl Program {
-    pub fn new() -> Self {
+    pub fn new(kind: TransitionKind, dither: Dither) -> Self {
+        let program = Self::link(Shader::new_fragment(kind));
+        program.link_arguments();
+        unsafe {
+            // Texture units are fixed, `from` is always bound to 0, `to` to 1, the noise to 2
+            // and the backdrop to 3
+            let from_location = gl::GetUniformLocation(program.id, c"from".as_ptr());
+            gl::Uniform1i(from_location, 0);
+            let to_location = gl::GetUniformLocation(program.id, c"to".as_ptr());
+            gl::Uniform1i(to_location, 1);
+            let noise_location = gl::GetUniformLocation(program.id, c"noise".as_ptr());
+            gl::Uniform1i(noise_location, 2);
+            let backdrop_location = gl::GetUniformLocation(program.id, c"backdrop".as_ptr());
+            gl::Uniform1i(backdrop_location, 3);
+            check_error("Texture Uniforms");
+            let dither_location = gl::GetUniformLocation(program.id, c"dither".as_ptr());
+            gl::Uniform1i(dither_location, dither.uniform());
+            check_error("Dither Uniform");
+        }
+        program
+    }
+
+    /// Program drawing colors and gradients, it uses the vertex array set up
+    /// by the transitions.
+    pub fn fill() -> Self {
+        Self::link(Shader::new_fill_fragment())
+    }
+
+    fn link(fragment_shader: Shader) -> Self {
         let vertex_shader = Shader::new_vertex();
-        let fragment_shader = Shader::new_fragment();
 
         unsafe {
             let shader_program = gl::CreateProgram();
@@ -46,27 +255,29 @@ impl Program {
             gl::AttachShader(shader_program, fragment_shader.id());
             check_error("Attach Shader to Program");
             // Link Program Parameters
-            let out_color = std::ffi::CStr::from_bytes_with_nul_unchecked(b"outColor\0");
+            let out_color = c"outColor";
             gl::BindFragDataLocation(shader_program, 0, out_color.as_ptr());
             check_error("Bind Fragement Data Location");
+            // Fixed locations allow all programs to share the same vertex array
+            gl::BindAttribLocation(shader_program, 0, c"position".as_ptr());
+            gl::BindAttribLocation(shader_program, 1, c"texcoord".as_ptr());
+            check_error("Bind Attribute Location");
             gl::LinkProgram(shader_program);
             check_error("Link Program");
             gl::UseProgram(shader_program);
             check_error("Use Program");
-            let program = Self {
+            Self {
                 id: shader_program,
                 _vertex_shader: vertex_shader,
                 _fragment_shader: fragment_shader,
-            };
-            program.link_arguments();
-            program
+            }
         }
     }
 
     fn link_arguments(&self) {
         debug!("Linking \"position\" argument");
         unsafe {
-            let pos = std::ffi::CStr::from_bytes_with_nul_unchecked(b"position\0");
+            let pos = c"position";
             let pos_attrib = gl::GetAttribLocation(self.id, pos.as_ptr());
             check_error("pre call");
             gl::EnableVertexAttribArray(pos_attrib as u32);
@@ -83,7 +294,7 @@ impl Program {
         }
         debug!("Linking \"texcoord\" argument");
         unsafe {
-            let texture = std::ffi::CStr::from_bytes_with_nul_unchecked(b"texcoord\0");
+            let texture = c"texcoord";
             let tex_attrib = gl::GetAttribLocation(self.id, texture.as_ptr());
             check_error("pre call");
             gl::EnableVertexAttribArray(tex_attrib as u32);
@@ -103,7 +314,7 @@ impl Program {
 }
 
 impl Context {
-    pub fn new() -> Self {
+    pub fn new(dither: Dither, resolution: &ScaledMode) -> Self {
         let vertices: [f32; 16] = [
             // Positions    // TexCoords
             -1.0, 1.0, 0.0, 0.0, // top-left
@@ -147,7 +358,14 @@ impl Context {
             check_error("Buffer Creation");
         }
 
-        let program = Program::new();
+        let programs = TransitionKind::ALL
+            .iter()
+            .map(|kind| {
+                debug!("Compiling program {{ transition: {:?} }}", kind);
+                (*kind, Program::new(*kind, dither))
+            })
+            .collect();
+        let fill = Program::fill();
 
         // SETUP THE TEXTURE TO BE USED & DRAW THE SCREEN THE INITIALLY
         let mut tex_from = 0u32;
@@ -155,52 +373,259 @@ impl Context {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
             gl::GenTextures(1, &mut tex_from);
-            let name = std::ffi::CStr::from_bytes_with_nul_unchecked(b"from\0");
-            let from_location = gl::GetUniformLocation(program.id, name.as_ptr());
-            gl::Uniform1i(from_location, 0);
 
             gl::ActiveTexture(gl::TEXTURE1);
             gl::GenTextures(1, &mut tex_to);
-            let name = std::ffi::CStr::from_bytes_with_nul_unchecked(b"to\0");
-            let to_location = gl::GetUniformLocation(program.id, name.as_ptr());
-            gl::Uniform1i(to_location, 1);
+        }
+        // Black until another backdrop is set
+        let mut tex_backdrop = 0u32;
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE3);
+            gl::GenTextures(1, &mut tex_backdrop);
+            Self::set_image(&Frame::Rgb8(vec![0; 3]), 1, 1, tex_backdrop);
+        }
+        let tex_noise = (dither == Dither::Noise).then(|| unsafe { Self::set_noise() });
+        let mut samplers = [0u32; 2];
+        unsafe {
+            gl::GenSamplers(samplers.len() as i32, samplers.as_mut_ptr());
+            check_error("sampler creation");
         }
         Self {
             _vao: vao,
             _ebo: ebo,
             _vbo: vbo,
-            tex_from,
-            tex_to,
-            shader_program: program,
+            tex_from: vec![tex_from],
+            tex_to: vec![tex_to],
+            tex_backdrop,
+            _tex_noise: tex_noise,
+            shared: [None, None],
+            layouts: [Layout::default(); 2],
+            samplers,
+            resolution: [resolution.width as f32, resolution.height as f32],
+            programs,
+            fill,
+        }
+    }
+
+    // The noise stays bound to its own texture unit, which is not used otherwise
+    unsafe fn set_noise() -> u32 {
+        let mut tex_noise = 0u32;
+        gl::ActiveTexture(gl::TEXTURE2);
+        gl::GenTextures(1, &mut tex_noise);
+        gl::BindTexture(gl::TEXTURE_2D, tex_noise);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::R16 as i32,
+            dither::NOISE_SIZE as i32,
+            dither::NOISE_SIZE as i32,
+            0,
+            gl::RED,
+            gl::UNSIGNED_SHORT,
+            dither::blue_noise().as_ptr() as *const gl::types::GLvoid,
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+        check_error("noise texture");
+        tex_noise
+    }
+
+    pub fn set_from(&mut self, frames: &[Frame], width: i32, height: i32) {
+        self.unshare(0);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            Self::set_frames(&mut self.tex_from, frames, width, height)
+        }
+    }
+
+    pub fn set_to(&mut self, frames: &[Frame], width: i32, height: i32) {
+        self.unshare(1);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE1);
+            Self::set_frames(&mut self.tex_to, frames, width, height)
         }
     }
 
-    pub fn set_from(&self, pic: &[u8], width: i32, height: i32) {
+    pub fn fill_from(&mut self, fill: &Fill, width: i32, height: i32) {
+        self.unshare(0);
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
-            self.set_image(pic, width, height, self.tex_from)
+            Self::set_frames(&mut self.tex_from, &[], width, height);
+            self.render_fill(fill, width, height, self.tex_from[0]);
         }
     }
 
-    pub fn set_to(&self, pic: &[u8], width: i32, height: i32) {
+    pub fn fill_to(&mut self, fill: &Fill, width: i32, height: i32) {
+        self.unshare(1);
         unsafe {
             gl::ActiveTexture(gl::TEXTURE1);
-            self.set_image(pic, width, height, self.tex_to)
+            Self::set_frames(&mut self.tex_to, &[], width, height);
+            self.render_fill(fill, width, height, self.tex_to[0]);
+        }
+    }
+
+    /// Show a shared image first, it is placed on the output while drawing.
+    pub fn set_from_shared(
+        &mut self,
+        image: Rc<SharedImage>,
+        placement: &Placement,
+        filter: Filter,
+    ) {
+        self.share(0, image, placement, filter);
+    }
+
+    pub fn set_to_shared(&mut self, image: Rc<SharedImage>, placement: &Placement, filter: Filter) {
+        self.share(1, image, placement, filter);
+    }
+
+    fn share(
+        &mut self,
+        side: usize,
+        image: Rc<SharedImage>,
+        placement: &Placement,
+        filter: Filter,
+    ) {
+        let geometry = ScaledMode {
+            width: self.resolution[0] as i32,
+            height: self.resolution[1] as i32,
+        };
+        self.layouts[side] = Layout::new(&image, placement, &geometry);
+        unsafe { self.layouts[side].configure(self.samplers[side], filter) };
+        self.shared[side] = Some(image);
+    }
+
+    fn unshare(&mut self, side: usize) {
+        self.shared[side] = None;
+        self.layouts[side] = Layout::default();
+    }
+
+    /// Show the first frame of the image below transparent parts of the wallpaper.
+    pub fn set_backdrop(&mut self, frames: &[Frame], width: i32, height: i32) {
+        if let Some(frame) = frames.first() {
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE3);
+                Self::set_image(frame, width, height, self.tex_backdrop);
+            }
+        }
+    }
+
+    pub fn fill_backdrop(&mut self, fill: &Fill, width: i32, height: i32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE3);
+            self.render_fill(fill, width, height, self.tex_backdrop);
+        }
+    }
+
+    // Draw the fill into the texture, from there on it is treated like any image.
+    // Gradients are kept at 16 bits per channel to avoid banding.
+    unsafe fn render_fill(&self, fill: &Fill, width: i32, height: i32, tex_id: u32) {
+        gl::BindTexture(gl::TEXTURE_2D, tex_id);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGBA16 as i32,
+            width,
+            height,
+            0,
+            gl::RGBA,
+            gl::UNSIGNED_SHORT,
+            std::ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        check_error("fill texture");
+
+        let mut framebuffer = 0u32;
+        gl::GenFramebuffers(1, &mut framebuffer);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_2D,
+            tex_id,
+            0,
+        );
+        check_error("fill framebuffer");
+
+        let id = self.fill.id;
+        gl::UseProgram(id);
+        let uniform = |name: &std::ffi::CStr| gl::GetUniformLocation(id, name.as_ptr());
+        gl::Uniform2f(uniform(c"size"), width as f32, height as f32);
+        match fill.shape {
+            Shape::Linear(angle) => {
+                let angle = angle.to_radians();
+                gl::Uniform1i(uniform(c"radial"), 0);
+                gl::Uniform2f(uniform(c"direction"), angle.sin(), -angle.cos());
+            }
+            Shape::Radial => gl::Uniform1i(uniform(c"radial"), 1),
         }
+        let colors: Vec<f32> = fill.stops.iter().flat_map(|stop| stop.color).collect();
+        let offsets: Vec<f32> = fill.stops.iter().map(|stop| stop.offset).collect();
+        gl::Uniform1i(uniform(c"count"), fill.stops.len() as i32);
+        gl::Uniform3fv(uniform(c"colors"), fill.stops.len() as i32, colors.as_ptr());
+        gl::Uniform1fv(
+            uniform(c"offsets"),
+            fill.stops.len() as i32,
+            offsets.as_ptr(),
+        );
+        check_error("fill uniforms");
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+        check_error("fill drawing");
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        gl::DeleteFramebuffers(1, &framebuffer);
     }
 
-    unsafe fn set_image(&self, pic: &[u8], width: i32, height: i32, tex_id: u32) {
+    unsafe fn set_frames(textures: &mut Vec<u32>, frames: &[Frame], width: i32, height: i32) {
+        // Keep the textures of the previous image and only adjust their number
+        if textures.len() < frames.len() {
+            let mut added = vec![0u32; frames.len() - textures.len()];
+            gl::GenTextures(added.len() as i32, added.as_mut_ptr());
+            textures.extend(added);
+        } else if textures.len() > frames.len().max(1) {
+            let removed = textures.split_off(frames.len().max(1));
+            gl::DeleteTextures(removed.len() as i32, removed.as_ptr());
+        }
+        check_error("texture allocation");
+        for (frame, tex_id) in frames.iter().zip(textures.iter()) {
+            Self::set_image(frame, width, height, *tex_id);
+        }
+    }
+
+    unsafe fn set_image(frame: &Frame, width: i32, height: i32, tex_id: u32) {
+        let (internal_format, format, kind, pixels) = match frame {
+            Frame::Rgb8(pixels) => (
+                gl::RGB,
+                gl::RGB,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const gl::types::GLvoid,
+            ),
+            Frame::Rgba8(pixels) => (
+                gl::RGBA8,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const gl::types::GLvoid,
+            ),
+            Frame::Rgba16(pixels) => (
+                gl::RGBA16,
+                gl::RGBA,
+                gl::UNSIGNED_SHORT,
+                pixels.as_ptr() as *const gl::types::GLvoid,
+            ),
+        };
         gl::BindTexture(gl::TEXTURE_2D, tex_id);
+        // Rows of RGB images are not padded
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
         gl::TexImage2D(
             gl::TEXTURE_2D,
             0,
-            gl::RGB as i32,
+            internal_format as i32,
             width,
             height,
             0,
-            gl::RGB,
-            gl::UNSIGNED_BYTE,
-            pic.as_ptr() as *const gl::types::GLvoid,
+            format,
+            kind,
+            pixels,
         );
         check_error("image load");
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
@@ -219,15 +644,59 @@ impl Context {
         check_error("set border");
     }
 
-    pub fn draw(&self, ratio: f32) {
+    /// Draw the transition between the given frames of both images.
+    pub fn draw(&self, ratio: f32, kind: TransitionKind, (from, to): (usize, usize)) {
+        let program = &self.programs[&kind];
         unsafe {
-            let name = std::ffi::CStr::from_bytes_with_nul_unchecked(b"ratio\0");
-            let ratio_location = gl::GetUniformLocation(self.shader_program.id, name.as_ptr());
+            self.bind(0, from, &self.tex_from);
+            self.bind(1, to, &self.tex_to);
+            gl::UseProgram(program.id);
+            check_error("Use Program");
+            let name = c"ratio";
+            let ratio_location = gl::GetUniformLocation(program.id, name.as_ptr());
             gl::Uniform1f(ratio_location, ratio);
+            self.set_layouts(program.id);
             gl::ClearColor(0.0, 0.0, 0.0, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT);
             gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
             check_error("Drawing");
         }
     }
+
+    // Bind the texture of the frame shown on a side, shared images are sampled
+    // with the sampler of their layout
+    unsafe fn bind(&self, side: usize, frame: usize, owned: &[u32]) {
+        gl::ActiveTexture(gl::TEXTURE0 + side as u32);
+        match &self.shared[side] {
+            Some(image) => {
+                gl::BindTexture(gl::TEXTURE_2D, image.textures[frame]);
+                gl::BindSampler(side as u32, self.samplers[side]);
+            }
+            None => {
+                gl::BindTexture(gl::TEXTURE_2D, owned[frame]);
+                gl::BindSampler(side as u32, 0);
+            }
+        }
+        check_error("bind texture");
+    }
+
+    unsafe fn set_layouts(&self, id: u32) {
+        let uniform = |name: &std::ffi::CStr| gl::GetUniformLocation(id, name.as_ptr());
+        let [from, to] = &self.layouts;
+        gl::Uniform2fv(uniform(c"resolution"), 1, self.resolution.as_ptr());
+        gl::Uniform4fv(uniform(c"area"), 2, [from.area, to.area].concat().as_ptr());
+        gl::Uniform4fv(
+            uniform(c"cover"),
+            2,
+            [from.cover, to.cover].concat().as_ptr(),
+        );
+        gl::Uniform2fv(uniform(c"lod"), 2, [from.lod, to.lod].concat().as_ptr());
+        gl::Uniform1iv(uniform(c"extend"), 2, [from.extend, to.extend].as_ptr());
+        gl::Uniform4fv(
+            uniform(c"background"),
+            2,
+            [from.background, to.background].concat().as_ptr(),
+        );
+        check_error("layout uniforms");
+    }
 }
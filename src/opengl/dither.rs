@@ -0,0 +1,50 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+mod noise;
+
+use clap::ArgEnum;
+
+/// Offsets added to the drawn colors, which break up the bands of smooth
+/// gradients on outputs with 8 bits per channel.
+#[derive(PartialEq, Debug, Clone, Copy, ArgEnum, Hash, Eq, Default)]
+pub enum Dither {
+    #[default]
+    None,
+    /// A regular 8x8 Bayer matrix.
+    Ordered,
+    /// Blue noise, which shows no visible pattern.
+    Noise,
+}
+
+impl Dither {
+    /// Value of the `dither` uniform of the transition shaders.
+    pub fn uniform(&self) -> i32 {
+        match self {
+            Dither::None => 0,
+            Dither::Ordered => 1,
+            Dither::Noise => 2,
+        }
+    }
+}
+
+/// Side length of the blue noise texture, which is repeated over the output.
+pub const NOISE_SIZE: usize = 64;
+
+/// Thresholds of a blue noise pattern, see `noise.rs` for how they were generated.
+pub fn blue_noise() -> &'static [u16] {
+    &noise::BLUE_NOISE
+}
@@ -0,0 +1,281 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Thresholds of a 64x64 blue noise pattern wrapping around at its edges. It
+// was generated with the void and cluster method, using a gaussian of
+// sigma 1.5 and an initial pattern with every tenth pixel set. Each rank r of
+// the 4096 pixels is stored as (2r + 1) * 8, the center of its share of the
+// 16 bit range. Generated by `examples/blue_noise.rs`.
+
+#[rustfmt::skip]
+pub static BLUE_NOISE: [u16; 64 * 64] = [
+    56648, 11512, 61304, 24280, 58024, 46456, 17784, 22520, 55160, 8680, 49208, 58184, 42712, 22552, 64920, 15336,
+    51592, 11720, 57160, 16168, 3192, 35528, 7752, 58536, 3560, 20216, 50648, 26952, 46760, 35768, 20280, 8296,
+    32360, 48072, 18728, 9448, 42536, 29880, 38104, 60296, 33448, 5688, 61704, 52824, 17064, 38232, 32024, 53496,
+    40264, 33208, 14152, 65112, 9400, 48856, 60552, 35960, 57096, 51400, 7784, 48536, 12008, 60840, 41752, 3112,
+    21288, 32680, 47320, 15128, 41608, 9944, 59608, 4888, 40312, 30920, 16936, 4312, 37704, 8792, 33976, 46440,
+    4808, 61208, 23192, 30312, 62840, 20312, 49288, 28056, 45480, 31416, 40584, 16328, 57544, 10440, 53128, 39784,
+    55752, 13624, 62072, 34920, 53528, 14552, 1336, 24136, 11464, 47192, 21736, 35848, 10152, 62392, 24664, 12168,
+    21864, 55560, 1752, 41816, 23320, 30328, 44152, 11768, 216, 22568, 64808, 17896, 35320, 6216, 47064, 26344,
+    64632, 4296, 53640, 26520, 1192, 34136, 28440, 50088, 13336, 45496, 63304, 28712, 55304, 25320, 60024, 19240,
+    28616, 37384, 6888, 42824, 10952, 52504, 40168, 13704, 55192, 9128, 60536, 1208, 32616, 43528, 25624, 17224,
+    2760, 27016, 45112, 3960, 25144, 48760, 56328, 43896, 58440, 38920, 2072, 30440, 55832, 4952, 44008, 59480,
+    6184, 45704, 28376, 50760, 15816, 3496, 56184, 25688, 47256, 38392, 29800, 43848, 24904, 52936, 15256, 37832,
+    49752, 43192, 18712, 36904, 63896, 44456, 16408, 61368, 36104, 120, 21816, 50840, 12248, 45064, 1560, 40552,
+    53320, 14824, 48744, 56456, 32856, 24344, 136, 64408, 19336, 37400, 24872, 52200, 21320, 4920, 58664, 46184,
+    64840, 36984, 20648, 57624, 40392, 8648, 20360, 31272, 15352, 26440, 65320, 43320, 18424, 49640, 29672, 16104,
+    33960, 62184, 11192, 36936, 60040, 33768, 40072, 17528, 54200, 13896, 6600, 56744, 2312, 62440, 32056, 8712,
+    14232, 29368, 7832, 57064, 11896, 51736, 23768, 7528, 25960, 57560, 40920, 6920, 35720, 17368, 57768, 31560,
+    7704, 64312, 25512, 2744, 17288, 59464, 36728, 30520, 5192, 44488, 12744, 47512, 63448, 38616, 13080, 29656,
+    7544, 51000, 11608, 31832, 16456, 64216, 35656, 5096, 53848, 8440, 50360, 12792, 25192, 38456, 2264, 52280,
+    39752, 24408, 18696, 53512, 7160, 21112, 64168, 4680, 28504, 60008, 33944, 49016, 16728, 41032, 22952, 55816,
+    35288, 60616, 40968, 22264, 31528, 2648, 39048, 47272, 52856, 32568, 14328, 62088, 29608, 48008, 23448, 11496,
+    43224, 20584, 34952, 46056, 40520, 8824, 48376, 22488, 54264, 61352, 33384, 6824, 28840, 18568, 51688, 35352,
+    23032, 41864, 60056, 5384, 45928, 26024, 49976, 59912, 41592, 21368, 33464, 57864, 7016, 63064, 21224, 57400,
+    8968, 48568, 888, 42520, 27208, 46392, 10904, 49816, 42248, 9576, 21576, 38744, 27224, 10888, 46024, 440,
+    17320, 25432, 3592, 48280, 54984, 17144, 62424, 10280, 19496, 3064, 46088, 21064, 54328, 3704, 62936, 36760,
+    50952, 55608, 6072, 61816, 27944, 52296, 15192, 42104, 10968, 27336, 17656, 56680, 41256, 9240, 59704, 408,
+    55720, 17160, 28120, 36536, 54760, 12328, 712, 17752, 29144, 47640, 2680, 40632, 28888, 46040, 34376, 14344,
+    27576, 64568, 32728, 59320, 15160, 57048, 30344, 36360, 18504, 52168, 63736, 3640, 58424, 52600, 30360, 59080,
+    50968, 43368, 63320, 13672, 37784, 26888, 44040, 34632, 55224, 28248, 60136, 37640, 9640, 42472, 14648, 27608,
+    696, 17496, 31016, 12104, 21928, 4040, 63560, 35032, 1544, 51480, 37864, 4184, 46936, 24504, 31928, 44168,
+    11112, 47880, 3176, 62920, 20088, 33080, 44808, 38856, 9224, 63816, 14776, 52744, 18216, 10664, 51448, 41464,
+    4136, 45016, 22328, 9304, 39480, 3272, 23160, 61336, 1320, 26408, 32344, 15144, 43832, 20072, 5352, 37336,
+    12216, 6456, 33752, 20888, 8072, 58824, 4344, 22744, 13240, 43144, 5880, 16760, 26920, 52552, 33784, 60072,
+    47688, 41304, 58872, 49528, 38600, 55864, 29896, 19304, 59432, 44312, 23304, 65224, 15384, 50376, 62120, 20328,
+    38520, 58136, 24232, 40888, 7944, 50904, 61096, 22616, 54456, 34680, 25768, 58808, 37576, 152, 61768, 19688,
+    31096, 54104, 16920, 50072, 34152, 54376, 44888, 13784, 56344, 46776, 7240, 50920, 34968, 9736, 65160, 22728,
+    55208, 27512, 57912, 45800, 52584, 32248, 48872, 65240, 38952, 56840, 33192, 50424, 64344, 22216, 6248, 19736,
+    10920, 25448, 7224, 33992, 15496, 42888, 9992, 46904, 26296, 13576, 7976, 30728, 36344, 2568, 12840, 27624,
+    6648, 32696, 12136, 52920, 30952, 15240, 27144, 4584, 13752, 43096, 6392, 20808, 47080, 32664, 24920, 55688,
+    11976, 38088, 5416, 61944, 26616, 19624, 6008, 40120, 34280, 17928, 41224, 23352, 60456, 28776, 40680, 46296,
+    31800, 16968, 39432, 1288, 14840, 25464, 9720, 18744, 648, 24248, 11336, 45224, 1992, 36920, 46232, 54584,
+    39464, 62808, 18856, 53880, 1432, 23736, 60568, 4632, 54552, 40232, 58680, 45960, 22024, 57512, 40824, 54088,
+    48584, 64152, 17912, 43992, 1848, 58568, 47832, 38200, 62616, 29576, 49032, 12088, 60488, 5304, 42872, 8040,
+    49272, 59064, 23672, 42024, 11064, 47128, 65176, 28664, 9976, 62520, 53400, 13048, 984, 55768, 15640, 3208,
+    48696, 8920, 63592, 22296, 42344, 61448, 36264, 43864, 53080, 31176, 60360, 19080, 28520, 13912, 58728, 30248,
+    3080, 35112, 45288, 28648, 64984, 48472, 37272, 30568, 18072, 33928, 1768, 15944, 52184, 8376, 31400, 17352,
+    3368, 36392, 26200, 56824, 21400, 33352, 9112, 18440, 52312, 3480, 57128, 40088, 27160, 17688, 65512, 36376,
+    27832, 15880, 33832, 3544, 56776, 31480, 14760, 50472, 22360, 4168, 30056, 38792, 48440, 24968, 35736, 61736,
+    37960, 25608, 50248, 34392, 54904, 4840, 29240, 57480, 15672, 48296, 7464, 38808, 53784, 42744, 9096, 23960,
+    15992, 51928, 12280, 5272, 33240, 19880, 12808, 52392, 8840, 63000, 49304, 28968, 38424, 63480, 25048, 43256,
+    59976, 10504, 46568, 5480, 38664, 65048, 44568, 25336, 35464, 15112, 22152, 33112, 53624, 10408, 47560, 20824,
+    1464, 44072, 63048, 48488, 18872, 37208, 40, 59352, 44360, 36440, 57528, 19512, 7032, 43352, 10840, 20936,
+    6120, 56632, 13368, 7080, 18136, 47976, 12264, 22632, 4104, 35304, 26136, 62200, 3736, 33608, 63512, 49560,
+    41432, 61048, 26088, 56376, 42632, 7336, 58856, 44520, 26056, 20840, 41160, 11544, 20152, 5656, 47416, 13176,
+    22680, 32264, 54424, 16344, 28760, 12904, 53976, 1240, 41848, 59272, 8264, 45720, 2792, 38904, 30264, 58616,
+    51784, 12392, 29320, 8808, 24840, 51960, 41480, 26312, 8392, 16440, 49848, 27256, 64472, 52072, 30376, 59192,
+    18824, 43576, 31256, 41112, 27960, 59656, 37688, 62744, 41912, 52248, 13112, 44392, 22008, 15224, 27176, 520,
+    20456, 7928, 37080, 17048, 50632, 24488, 38472, 232, 34568, 56488, 4408, 60856, 53560, 34264, 58280, 37432,
+    50328, 248, 41272, 60712, 50728, 7352, 22776, 61320, 30648, 50296, 26424, 63464, 19784, 55928, 14296, 5800,
+    34312, 21848, 54840, 40280, 58840, 5736, 17816, 53752, 32632, 61976, 1656, 11880, 35048, 15736, 2856, 46152,
+    26776, 1224, 65336, 51672, 21688, 2248, 45560, 8904, 30504, 19864, 59016, 8600, 50488, 56968, 45880, 34664,
+    58152, 30472, 46728, 2216, 59784, 30088, 15544, 64280, 46744, 13528, 31896, 45352, 25944, 2776, 18248, 8088,
+    64056, 27368, 11528, 19912, 31976, 39992, 47336, 14472, 6360, 18328, 39272, 12344, 35256, 49400, 25208, 40872,
+    64120, 45944, 2632, 35432, 13544, 30808, 63784, 11128, 47160, 24024, 39064, 45320, 55256, 23432, 39928, 53272,
+    60120, 35128, 16040, 10712, 55896, 32584, 16680, 24824, 55400, 1016, 33000, 38024, 25496, 4776, 40040, 10040,
+    53208, 13496, 62280, 22184, 40840, 8280, 54568, 20408, 6504, 52872, 23128, 16136, 39400, 50696, 29528, 43912,
+    20760, 56312, 35608, 45976, 2712, 63368, 26712, 37048, 55592, 45464, 3848, 58088, 29480, 504, 60312, 17208,
+    8472, 27272, 18488, 61512, 49192, 43944, 21048, 36792, 4744, 56280, 18632, 29400, 5240, 62872, 33016, 12456,
+    49592, 22872, 46872, 38568, 5976, 49064, 36824, 64264, 47208, 15000, 43208, 62568, 17448, 31112, 64968, 19000,
+    24520, 43736, 5208, 34216, 14120, 48936, 32600, 42696, 26936, 38728, 59176, 8184, 65128, 11992, 55320, 33592,
+    14744, 48184, 5464, 25096, 54664, 17576, 9624, 58952, 21832, 32712, 52024, 23816, 42616, 10616, 45176, 36888,
+    56104, 50616, 32904, 9688, 24584, 1352, 57800, 27432, 42152, 13800, 60696, 49432, 10424, 43016, 18392, 6344,
+    37016, 9064, 28952, 60440, 24168, 57960, 11912, 4488, 29224, 53032, 22968, 6328, 51768, 12376, 47816, 3016,
+    37592, 50792, 28936, 54232, 63672, 25784, 2584, 61496, 11368, 49576, 3128, 34456, 43000, 23544, 1384, 60936,
+    7112, 38968, 62152, 13592, 36648, 51224, 31464, 808, 41096, 13432, 8424, 64744, 16472, 53112, 21208, 30040,
+    5944, 14056, 43448, 56760, 39144, 15576, 51560, 8312, 48600, 31336, 2344, 35224, 22712, 58984, 27704, 56568,
+    19848, 63176, 3096, 42040, 17512, 30200, 44120, 20392, 39544, 9480, 58488, 28536, 41544, 33880, 56152, 27480,
+    61464, 8728, 18120, 42552, 9928, 38056, 18760, 55704, 35992, 17432, 28280, 54008, 19384, 49160, 37064, 26488,
+    51752, 22280, 30792, 57144, 4200, 42680, 20616, 48904, 61544, 28136, 46712, 36488, 31144, 4328, 62008, 49496,
+    25400, 63416, 20440, 4568, 27848, 65192, 33400, 19320, 61912, 24440, 53896, 41624, 14584, 51720, 264, 44536,
+    54536, 32040, 48232, 13816, 53592, 632, 51464, 61784, 32776, 14280, 46488, 3432, 61160, 8200, 21032, 41992,
+    14200, 35176, 58168, 952, 21912, 45736, 51096, 6968, 24184, 45432, 62552, 13960, 31864, 5112, 59304, 17544,
+    41640, 10648, 45448, 17128, 28392, 65256, 11672, 35944, 16024, 55800, 2360, 19576, 57368, 40648, 12536, 35592,
+    1880, 39960, 52216, 34824, 47496, 9192, 42792, 3384, 39656, 10200, 17672, 64536, 7416, 38248, 30392, 15320,
+    41048, 10984, 22472, 37800, 64760, 34872, 23512, 7000, 42968, 55448, 19432, 34648, 24392, 15656, 46680, 1928,
+    53544, 23064, 48552, 27720, 59800, 32824, 13512, 30600, 58472, 1576, 41144, 9256, 57752, 44760, 12952, 29928,
+    64328, 1816, 52904, 37944, 8488, 46632, 25480, 52680, 6568, 23992, 39304, 50984, 8104, 24312, 46472, 18296,
+    58712, 30744, 10392, 16776, 59336, 23080, 54504, 26664, 58312, 33800, 45832, 29352, 23592, 48136, 62296, 5672,
+    26680, 50520, 58456, 6232, 27528, 11480, 47736, 15912, 26792, 1640, 64600, 39864, 50600, 58120, 36808, 64184,
+    31496, 6584, 40440, 11640, 52776, 4072, 65000, 39576, 16504, 53016, 35384, 26232, 20200, 39096, 54856, 8216,
+    47928, 20552, 32472, 60984, 22840, 54920, 2840, 31064, 42840, 63544, 32216, 14008, 61080, 28824, 54168, 7048,
+    42264, 22920, 56424, 44296, 312, 32376, 12040, 50568, 16088, 6136, 52088, 2040, 58600, 10056, 34552, 20952,
+    60968, 1480, 33064, 45160, 19672, 40776, 54728, 59528, 37720, 48792, 29848, 11960, 4264, 29160, 9384, 25080,
+    44376, 17336, 61832, 36248, 19896, 42856, 24952, 8584, 48504, 21592, 6536, 64248, 48216, 88, 33576, 24744,
+    36312, 57336, 13464, 4504, 40984, 16664, 36520, 57288, 18456, 9368, 47528, 21544, 43880, 840, 33416, 61688,
+    14248, 49944, 5160, 26872, 38872, 62408, 20120, 36328, 44056, 63336, 21608, 37160, 42760, 17960, 55096, 46168,
+    37240, 18648, 10456, 51912, 61624, 2728, 30120, 9048, 19960, 6040, 54024, 17848, 42920, 54952, 19704, 48264,
+    12296, 55416, 28744, 5544, 49656, 14712, 54968, 29064, 61576, 32280, 44632, 14616, 29336, 51384, 15976, 62536,
+    5048, 42664, 26840, 50024, 29816, 59640, 11352, 48344, 27496, 59816, 3608, 54888, 37880, 12120, 51368, 20344,
+    38120, 29768, 64376, 18584, 55432, 7992, 47176, 2968, 27656, 11144, 31656, 14184, 52968, 27384, 3400, 13480,
+    29864, 55992, 42360, 24680, 16232, 36040, 46424, 23208, 62376, 32984, 44840, 23944, 62712, 32408, 6024, 61016,
+    34408, 1304, 42168, 23784, 63240, 34504, 552, 46200, 12696, 3000, 58504, 36568, 9816, 60184, 21560, 38696,
+    11560, 47592, 19272, 63704, 7960, 44600, 21768, 168, 40216, 13720, 35144, 29416, 17944, 65448, 26104, 45608,
+    2936, 11032, 46888, 35400, 13352, 51176, 22808, 59496, 40504, 56008, 46840, 61432, 6696, 33704, 65064, 43768,
+    22120, 4536, 63848, 31736, 7064, 56808, 12520, 39688, 51208, 14504, 57272, 184, 37736, 14936, 52664, 39592,
+    22392, 51496, 59400, 9208, 30552, 41192, 58008, 18904, 38984, 24360, 52888, 19480, 41832, 3688, 45592, 56296,
+    31080, 54152, 1704, 34696, 14952, 53240, 33096, 64424, 50888, 23528, 45032, 58200, 4664, 41512, 7624, 32232,
+    58888, 52456, 23832, 5864, 42184, 28088, 33624, 16264, 9080, 24424, 744, 20104, 41352, 23928, 47944, 8888,
+    39912, 53656, 13016, 38184, 50536, 27768, 61032, 2120, 26648, 8136, 40600, 28808, 10104, 46824, 26968, 3720,
+    13736, 31448, 18104, 48200, 13128, 21752, 6488, 52008, 32952, 43816, 7368, 28040, 56728, 33176, 25528, 5592,
+    15304, 23384, 40344, 57464, 26216, 39496, 5992, 29176, 16824, 7208, 52408, 11176, 21880, 49688, 56952, 12824,
+    39384, 18360, 30584, 60904, 53832, 1800, 64072, 44696, 52440, 31224, 38136, 50280, 10488, 54872, 15592, 59128,
+    26392, 17720, 48424, 328, 21128, 43336, 18264, 48168, 35544, 65144, 16984, 51800, 59240, 20728, 63688, 42936,
+    58072, 46120, 5176, 38216, 54632, 60760, 26456, 10856, 64520, 15272, 59592, 49624, 12408, 17880, 65208, 50680,
+    35864, 62216, 9528, 48520, 3880, 20264, 56120, 43064, 60728, 38488, 31608, 63032, 27464, 35576, 16488, 25288,
+    63576, 728, 44264, 15624, 37992, 21240, 11704, 36120, 4088, 62136, 14680, 59768, 28344, 36584, 1528, 32760,
+    6152, 45144, 29976, 62328, 54472, 9592, 32456, 6376, 55656, 21624, 45992, 32104, 3336, 35832, 16648, 7880,
+    35096, 24728, 64824, 27736, 2056, 33368, 41928, 49080, 29272, 3976, 21704, 36664, 872, 44104, 39368, 8328,
+    27752, 43752, 18600, 30104, 59928, 45192, 14360, 9880, 25880, 1944, 19096, 40728, 3304, 46856, 54824, 5576,
+    48648, 35160, 56024, 7816, 25704, 46584, 57448, 18344, 27912, 47048, 22200, 4712, 44712, 19144, 62776, 50184,
+    56888, 11208, 35448, 23752, 14632, 37464, 51064, 25416, 41128, 11688, 5256, 24936, 42504, 56536, 50456, 29720,
+    55736, 10536, 20232, 43976, 51576, 15704, 19992, 5512, 54488, 39208, 47240, 31032, 60792, 53368, 21448, 14264,
+    58104, 1176, 51864, 12072, 34168, 24632, 50776, 36744, 58552, 48040, 54712, 14408, 59560, 10296, 30488, 42808,
+    21256, 27448, 12648, 50392, 62664, 30968, 6952, 52952, 39080, 8024, 55288, 33272, 57704, 8616, 40856, 22888,
+    38408, 60584, 3224, 42136, 56504, 4472, 63944, 14040, 59864, 34536, 53064, 61992, 14376, 9464, 24040, 1080,
+    40152, 49704, 14424, 36680, 7688, 62952, 45528, 34808, 23800, 62312, 17192, 10552, 25160, 5928, 30072, 47704,
+    37192, 22760, 61256, 39160, 6632, 64952, 3448, 30632, 21000, 8664, 32936, 43512, 23416, 37560, 18920, 58360,
+    9656, 60408, 41448, 33864, 2920, 16296, 40744, 24856, 65272, 12776, 42200, 25816, 16392, 49176, 29784, 13928,
+    20184, 28184, 50264, 18472, 27048, 45656, 19976, 30872, 824, 48808, 18616, 28200, 37096, 47480, 32744, 60376,
+    21144, 4824, 61400, 31352, 55112, 25672, 9768, 57992, 13848, 1672, 42568, 56872, 49128, 35480, 63016, 3992,
+    55000, 31704, 15048, 48328, 19176, 41656, 55640, 16696, 45912, 63384, 26744, 4872, 49800, 64904, 1448, 33544,
+    50872, 4248, 17736, 23272, 44776, 58904, 48456, 344, 34440, 19720, 51304, 3912, 36168, 64360, 2088, 54808,
+    5624, 46008, 9032, 63208, 32968, 10776, 39336, 56856, 24536, 43288, 8984, 57640, 2488, 64712, 17560, 44024,
+    53336, 29256, 46984, 22600, 280, 39832, 50232, 28920, 37768, 51416, 32424, 7480, 20664, 12152, 41960, 18984,
+    10344, 42904, 5528, 28360, 53576, 24088, 11592, 37416, 456, 39736, 12664, 53992, 16856, 28072, 45688, 14696,
+    38936, 29016, 64392, 53144, 8168, 36280, 21272, 11096, 56216, 30296, 59160, 46248, 11864, 23720, 43032, 33816,
+    61800, 40200, 15848, 53048, 1896, 59048, 49352, 6776, 36424, 15464, 32136, 50712, 20920, 40424, 11000, 5768,
+    36504, 15064, 8856, 57720, 44680, 12984, 20136, 4120, 61416, 18056, 25928, 64664, 37448, 56088, 28216, 60168,
+    24984, 51192, 63992, 36216, 8568, 60104, 31432, 49960, 57496, 22648, 60248, 34040, 41560, 9864, 54792, 24712,
+    57656, 11016, 46968, 31816, 13992, 27864, 62968, 49992, 38264, 6664, 17112, 28104, 38888, 59736, 9352, 51336,
+    13256, 29960, 23640, 36776, 43608, 25592, 13560, 21992, 53480, 62760, 45768, 4968, 26552, 52984, 31048, 56984,
+    25384, 63496, 41576, 18040, 34840, 65352, 32088, 54120, 42776, 11416, 48024, 4856, 44584, 15448, 568, 47112,
+    34328, 16712, 1912, 44472, 15720, 46504, 5128, 18376, 27784, 6312, 47544, 19640, 2472, 61272, 36024, 4648,
+    43624, 21176, 1000, 40296, 57832, 5496, 42648, 15784, 25720, 44280, 63432, 1416, 53720, 18520, 31784, 22232,
+    2440, 49096, 56136, 6104, 17304, 31640, 64552, 41528, 2504, 28632, 12312, 39560, 59544, 14728, 44872, 19368,
+    49256, 2696, 30216, 53096, 4360, 26280, 46104, 7496, 24072, 35496, 55016, 30712, 22584, 51288, 32072, 8008,
+    54280, 40104, 21720, 57944, 26808, 34856, 62472, 42296, 53384, 35880, 10584, 29192, 51896, 22536, 48680, 18200,
+    63112, 35240, 55384, 25368, 17704, 51352, 32536, 2328, 54680, 10312, 23368, 34776, 48616, 5320, 57224, 45752,
+    64856, 34936, 11400, 61112, 46376, 54136, 9320, 34584, 48248, 19208, 56584, 23880, 8504, 36152, 584, 61000,
+    10136, 39112, 21528, 47752, 10680, 58648, 14456, 39016, 59896, 1096, 15832, 60808, 10696, 39352, 63912, 23864,
+    12728, 59848, 30168, 9496, 51320, 2808, 23336, 9336, 15432, 64488, 43048, 56232, 38440, 12968, 31768, 8520,
+    28488, 14872, 49720, 9832, 62264, 37752, 20472, 60648, 47384, 31368, 58040, 13608, 42392, 27352, 15080, 39608,
+    7560, 18808, 28232, 39976, 21336, 104, 26696, 15400, 61192, 7608, 43640, 34360, 63224, 49864, 27880, 41800,
+    32504, 56248, 15560, 62504, 37288, 23016, 50936, 29944, 19032, 47144, 26904, 42280, 3928, 55576, 16616, 45272,
+    36968, 5608, 49448, 42120, 19288, 55464, 39672, 48312, 32184, 1112, 24152, 16568, 5432, 62648, 44328, 52696,
+    57928, 5016, 43240, 29912, 3864, 46328, 11784, 28168, 6904, 39624, 19608, 51608, 8408, 62696, 33720, 24104,
+    44616, 57416, 50136, 4936, 33496, 59000, 43176, 52488, 38344, 30760, 54248, 4008, 16536, 21016, 55144, 13272,
+    23560, 3944, 44920, 28552, 6296, 43720, 3512, 57304, 10088, 64024, 36856, 53000, 20872, 34760, 28584, 2296,
+    61672, 26184, 15288, 33288, 65368, 13384, 28472, 58776, 20248, 51032, 59944, 30840, 48360, 26008, 3032, 38824,
+    22984, 36136, 19752, 58792, 34424, 24216, 54360, 42376, 16952, 65400, 2600, 37320, 22664, 47768, 968, 53672,
+    30856, 13144, 24888, 63832, 16008, 48408, 10872, 23176, 2904, 20744, 14024, 48728, 32152, 44984, 5816, 64680,
+    50216, 36712, 60232, 12488, 34296, 55128, 16200, 40712, 33848, 22440, 6280, 13944, 48120, 9608, 58376, 50104,
+    20024, 43544, 53800, 680, 25272, 7848, 36072, 4904, 41704, 11432, 37512, 7640, 57432, 34472, 19128, 12232,
+    65384, 48984, 10760, 52328, 15512, 63400, 1592, 33528, 49368, 25240, 53256, 30888, 60152, 11848, 41720, 17384,
+    61880, 3416, 36632, 42232, 7512, 28152, 35896, 56200, 41896, 65464, 28264, 58264, 40024, 10792, 27000, 34488,
+    18152, 8952, 25560, 51880, 19528, 64296, 25000, 49416, 2136, 53944, 43112, 31992, 63096, 24264, 37496, 6728,
+    31240, 11576, 35208, 57784, 40904, 46616, 52056, 15208, 63352, 26760, 45784, 21352, 14312, 42408, 55176, 46360,
+    30904, 424, 25640, 38168, 7288, 40616, 21784, 58232, 8056, 13832, 44344, 5288, 16792, 28696, 56904, 35672,
+    9848, 47624, 55784, 22104, 52632, 60088, 18680, 6264, 31912, 50152, 9416, 1624, 23912, 60744, 47224, 2408,
+    57816, 42072, 47672, 792, 40376, 7720, 32008, 12600, 60328, 26728, 17832, 56040, 296, 40792, 13192, 54216,
+    46216, 63656, 5000, 21432, 16152, 60424, 31384, 22136, 53736, 3160, 33304, 50200, 64136, 2184, 25112, 8360,
+    16904, 56664, 44664, 60344, 28408, 49880, 11272, 45416, 29048, 62248, 34616, 56440, 39704, 49000, 5832, 21480,
+    39128, 29592, 17864, 11384, 33160, 2424, 44216, 61928, 12680, 18024, 45864, 35192, 52648, 16056, 38280, 53816,
+    22792, 30456, 14888, 59288, 27800, 53224, 46696, 20792, 37608, 44968, 8232, 29384, 46808, 19400, 61224, 27288,
+    16808, 23848, 37816, 51272, 29032, 10600, 1608, 45080, 38776, 18312, 55848, 6712, 28856, 38072, 60216, 52376,
+    40248, 34008, 20680, 5032, 16552, 32792, 55240, 17608, 38040, 8, 18968, 23608, 10168, 60632, 25992, 52760,
+    65288, 600, 58936, 46264, 26584, 50744, 37352, 23896, 53432, 39192, 26536, 63288, 20600, 7384, 29640, 13880,
+    6792, 63976, 38504, 21096, 11816, 35928, 3800, 57176, 6056, 64696, 14920, 36600, 59384, 5144, 34120, 41768,
+    1864, 58216, 44424, 6808, 56056, 39640, 62904, 26072, 8696, 59624, 13032, 41320, 19816, 10248, 32648, 22376,
+    4440, 63144, 12424, 52616, 41288, 64728, 3896, 24200, 59112, 42008, 52232, 46408, 32520, 2232, 43384, 13416,
+    33736, 23464, 41672, 6088, 63640, 15480, 9000, 30536, 1128, 59208, 6440, 12184, 41016, 49768, 61640, 43080,
+    35064, 49224, 2872, 54776, 45576, 62456, 17000, 26152, 33432, 50040, 23048, 52792, 11624, 25576, 48712, 9800,
+    52136, 28456, 11448, 33144, 18840, 46952, 14104, 35016, 49544, 30152, 23400, 47096, 58248, 51048, 43464, 13688,
+    49784, 26328, 45896, 31000, 22344, 9432, 36232, 48056, 10568, 30664, 7176, 15096, 62824, 37144, 19544, 47896,
+    7736, 54056, 12760, 35688, 20904, 39800, 56360, 46664, 19560, 34104, 44232, 56552, 31544, 23688, 360, 19464,
+    55912, 10520, 25176, 33048, 8344, 29512, 42584, 55048, 11320, 40136, 1976, 43432, 30680, 56712, 21160, 65416,
+    35272, 15176, 62344, 49464, 24776, 5224, 57688, 20424, 2456, 63960, 37000, 5752, 31592, 760, 17640, 60952,
+    36200, 9912, 56920, 1064, 60920, 43704, 27128, 54408, 16072, 63864, 24808, 54184, 27304, 11160, 58392, 28904,
+    16280, 45048, 57208, 29736, 51432, 3576, 25752, 62600, 10728, 51016, 24792, 15768, 4696, 53912, 34056, 45640,
+    28424, 16584, 60504, 41736, 15416, 51512, 1160, 20520, 60776, 27992, 18664, 63192, 6200, 38008, 16376, 3672,
+    43560, 22248, 39880, 920, 60472, 37976, 29208, 53304, 43928, 11304, 52728, 16312, 62040, 25064, 54312, 30008,
+    3528, 42728, 18952, 38584, 14600, 50808, 19656, 1960, 32840, 45240, 37912, 2664, 49144, 41208, 3816, 50856,
+    38360, 25128, 1688, 18536, 60600, 44088, 14168, 31752, 40952, 4216, 61752, 37480, 47352, 65016, 13400, 7768,
+    58520, 38376, 47720, 5368, 64792, 24328, 37656, 45672, 7576, 48952, 32872, 55272, 13288, 51528, 45848, 31960,
+    57384, 7672, 53448, 30424, 13320, 44936, 7912, 15896, 39896, 22056, 27544, 44504, 35080, 9544, 39416, 46600,
+    23256, 64104, 28872, 54344, 33912, 5640, 59576, 40696, 56408, 6680, 20536, 59416, 17272, 32200, 22504, 61384,
+    4984, 64200, 41416, 11288, 34232, 7400, 50056, 21384, 54072, 17400, 29560, 9784, 20856, 27592, 41080, 52344,
+    23240, 3656, 30136, 21464, 34248, 55544, 13224, 30984, 53864, 15608, 3144, 40408, 22408, 27640, 61848, 11048,
+    25864, 47016, 17768, 36616, 55976, 20776, 64888, 33256, 59512, 7096, 56168, 3832, 49320, 20632, 58920, 5912,
+    15960, 51128, 11256, 6760, 22824, 47464, 29288, 10824, 23656, 50408, 30024, 9960, 43400, 57352, 12872, 34712,
+    20376, 31208, 47288, 58584, 27112, 38680, 64872, 616, 36056, 58968, 48632, 40184, 57608, 6408, 18408, 35640,
+    62056, 12888, 51112, 57736, 8776, 44552, 5064, 63080, 24056, 36552, 62024, 47608, 9144, 35624, 24, 19944,
+    41240, 63768, 2824, 27080, 9896, 48968, 25256, 1832, 47448, 30616, 37528, 14440, 63720, 29624, 13096, 52536,
+    36872, 31512, 44792, 57576, 39288, 64584, 17080, 35560, 61240, 14088, 40056, 65432, 25224, 5896, 45208, 55480,
+    50168, 14536, 6520, 21496, 54616, 16120, 24552, 46136, 8632, 25976, 13864, 2168, 32392, 44184, 49896, 1256,
+    31304, 43672, 19592, 40936, 16360, 27560, 50664, 18792, 41176, 10232, 27096, 19016, 59256, 43496, 57016, 49912,
+    34072, 13640, 51080, 60264, 42056, 5400, 35704, 55032, 12056, 18280, 60680, 24648, 39848, 2552, 43960, 25800,
+    62680, 1272, 20168, 26632, 12552, 2952, 43656, 52424, 4520, 46072, 34344, 1048, 52264, 37624, 28984, 8872,
+    25896, 38312, 52808, 32920, 2616, 42328, 11656, 57976, 32888, 44648, 64040, 51816, 23704, 60280, 14392, 56472,
+    24376, 9560, 64088, 2280, 37368, 60664, 34888, 664, 56520, 45512, 4552, 52360, 31288, 6552, 16216, 28680,
+    7272, 24456, 38712, 19160, 30936, 57848, 16248, 41336, 26984, 52040, 44856, 9160, 54520, 33896, 57672, 19352,
+    10264, 40664, 49928, 61480, 33224, 55496, 24568, 9512, 28008, 19192, 54936, 22424, 14488, 47368, 17800, 61144,
+    376, 43128, 63528, 10216, 47800, 61960, 27816, 52712, 16632, 21976, 6856, 35976, 17240, 8456, 28792, 38760,
+    46520, 53192, 33128, 25912, 55880, 11752, 23112, 48392, 13000, 29432, 60824, 14968, 39240, 23576, 53464, 37128,
+    60888, 55336, 4392, 45816, 12472, 50440, 22456, 63272, 6472, 32168, 392, 22088, 49112, 17176, 7144, 48152,
+    34904, 55368, 15800, 7800, 46536, 17976, 37672, 63128, 41496, 59096, 10072, 29496, 61864, 4616, 53352, 35752,
+    57256, 16888, 22856, 29704, 37112, 19112, 6424, 38632, 3288, 41976, 55512, 27192, 47304, 41000, 62856, 4600,
+    16424, 21672, 7128, 50008, 44728, 5784, 31672, 65080, 38840, 21896, 34200, 50312, 10808, 64616, 2152, 47000,
+    11800, 17592, 34744, 64504, 25656, 1512, 37176, 10328, 43784, 58056, 36184, 65528, 11224, 31720, 41784, 59144,
+    28312, 3768, 24296, 37224, 29992, 56, 48888, 14664, 32552, 3320, 48664, 37032, 43272, 32808, 24120, 11928,
+    27896, 49672, 5704, 59960, 13768, 56392, 33640, 49512, 61592, 31128, 12024, 60872, 2536, 53608, 12712, 34792,
+    57000, 37848, 60520, 13448, 19064, 39944, 53288, 17480, 7864, 57896, 2392, 42312, 26360, 44952, 32296, 20968,
+    41384, 26856, 52840, 9704, 40760, 58632, 29832, 47912, 19224, 25016, 14904, 42600, 26824, 61128, 1720, 22168,
+    12936, 64456, 43416, 53704, 60392, 22040, 56600, 7192, 52120, 25832, 17256, 56792, 7256, 19768, 63928, 45624,
+    8744, 34600, 42440, 51640, 1400, 45384, 21656, 9672, 25032, 44904, 18888, 36840, 23096, 31688, 19928, 49480,
+    26472, 72, 42488, 28600, 63256, 24600, 1784, 45128, 27928, 47864, 19416, 62104, 7448, 17416, 58408, 5960,
+    62792, 48088, 2984, 32120, 18184, 52104, 14072, 62168, 5336, 55960, 49736, 3464, 53960, 20040, 46552, 39224,
+    31192, 49240, 19256, 11080, 5080, 40472, 27400, 35416, 20488, 62584, 45368, 12632, 27240, 50824, 2376, 37896,
+    21080, 55624, 14856, 26040, 39448, 29464, 65480, 16184, 54744, 472, 50584, 9176, 58744, 44248, 6744, 65304,
+    11240, 47656, 32488, 9288, 48824, 36088, 58760, 11832, 55528, 36952, 13160, 30776, 54392, 35800, 49608, 29304,
+    10376, 36296, 22936, 57032, 44824, 6616, 24008, 34984, 40488, 30824, 10184, 39032, 33032, 14136, 51832, 9752,
+    56072, 6168, 36008, 26600, 47992, 13656, 64936, 44744, 10632, 38328, 936, 33672, 59880, 39816, 16600, 59032,
+    28728, 3752, 62728, 19832, 10472, 52472, 4760, 41368, 34728, 27032, 63752, 39320, 14568, 51944, 28296, 39720,
+    23624, 59752, 16840, 57240, 3784, 14984, 30408, 42456, 21512, 4024, 51704, 40360, 22072, 488, 14792, 40536,
+    53768, 16744, 61176, 12200, 38536, 29112, 60200, 1032, 46920, 17096, 63160, 22312, 56936, 6936, 36408, 26248,
+    62888, 16520, 42424, 61064, 33560, 52568, 18232, 3624, 58696, 28568, 54600, 23000, 46792, 10024, 31576, 51848,
+    12568, 40328, 34088, 47032, 58296, 35816, 23480, 57112, 12440, 47400, 5448, 23976, 33320, 2024, 17032, 54296,
+    4280, 44200, 21304, 52152, 40456, 22904, 61608, 6984, 33336, 63880, 25848, 8248, 61288, 44440, 56696, 24696,
+    4056, 46344, 30696, 2008, 49048, 20008, 51240, 12920, 55352, 27064, 4792, 43160, 27976, 61656, 18936, 45096,
+    904, 24472, 54696, 3256, 21192, 8536, 39176, 31848, 47576, 15752, 41064, 5560, 18168, 65096, 4728, 42984,
+    59688, 53416, 8552, 30184, 2200, 16872, 49336, 7656, 30232, 59672, 17624, 42216, 57080, 45336, 61720, 35912,
+    29688, 13208, 34024, 5848, 27320, 45304, 51160, 18552, 56264, 44136, 15864, 48840, 32312, 10936, 28328, 65032,
+    19448, 39528, 55672, 25304, 63800, 9272, 31880, 41400, 21960, 35784, 51544, 12584, 48104, 4152, 53688, 31624,
+    38648, 50552, 11944, 30280, 46648, 59224, 26264, 55944, 21800, 8152, 62360, 51624, 34184, 26120, 48920, 21944,
+    536, 25352, 15368, 64232, 42088, 26168, 61560, 44408, 21416, 37304, 52520, 10744, 26568, 7320, 21640, 10008,
+    48776, 63624, 53928, 37928, 59992, 12360, 1144, 36696, 10120, 29096, 2104, 58344, 17992, 38152, 5720, 50344,
+    35000, 7896, 13304, 43480, 17016, 37256, 56616, 3240, 64776, 8760, 59368, 20296, 33656, 40568, 15528, 10360,
+    59832, 20504, 35336, 62488, 15032, 41688, 1496, 12856, 50120, 36472, 27688, 12504, 45000, 57320, 14808, 36456,
+    31160, 45256, 50504, 20712, 54648, 11736, 38552, 3352, 14216, 46280, 1368, 62232, 31944, 47848, 38296, 57880,
+    18776, 856, 24616, 9016, 19048, 31320, 65496, 47960, 22696, 53160, 34520, 41944, 23224, 54440, 45544, 14520,
+    23496, 59720, 51144, 33688, 4424, 47432, 26376, 15688, 43688, 29752, 39768, 200, 57592, 23144, 64440, 29000,
+    46312, 7432, 43592, 4376, 24760, 54040, 33480, 63608, 43800, 4232, 59448, 23288, 1736, 39256, 8936, 62984,
+    17464, 39512, 6840, 35512, 4456, 29544, 51992, 33512, 64648, 25736, 35368, 20056, 13064, 53176, 3048, 27672,
+    42952, 32440, 40008, 47784, 55064, 43304, 25544, 15016, 39000, 61896, 7304, 12616, 64008, 2888, 29448, 61528,
+    41880, 776, 28024, 57192, 20984, 62632, 8120, 51976, 19800, 49832, 13976, 27416, 45400, 7592, 49384, 2520,
+    18008, 58328, 26504, 51656, 37544, 18088, 6872, 20696, 29128, 15928, 40808, 32328, 55080, 20568, 29080, 51256,
+];
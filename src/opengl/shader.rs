@@ -18,6 +18,7 @@ use gl::types::GLenum;
 use log::error;
 
 use super::error::check_error;
+use super::transition::TransitionKind;
 
 #[derive(Debug)]
 pub struct Shader {
@@ -37,20 +38,109 @@ void main()
 }
 \0";
 
-const FRAGSHADER: &[u8] = b"#version 150 core
+// The body of `main` is filled in by the individual transitions. Their result
+// is drawn over the backdrop, colors of all textures are premultiplied by alpha,
+// and dithered to break up bands of smooth gradients in 8 bit outputs.
+// Transitions read the images with `fromColor` and `toColor`, which place
+// shared images on the output. Images placed by the CPU span the output.
+const FRAGSHADER: &str = "#version 150 core
 in vec2 Texcoord;
 
 out vec4 outColor;
 
 uniform sampler2D from;
 uniform sampler2D to;
+uniform sampler2D noise;
+uniform sampler2D backdrop;
 uniform float ratio;
+uniform int dither;
+uniform vec2 resolution;
+uniform vec4 area[2];
+uniform vec4 cover[2];
+uniform vec2 lod[2];
+uniform int extend[2];
+uniform vec4 background[2];
+
+const float SOFTNESS = 0.05;
+
+vec4 place(sampler2D image, vec2 coord, int side)
+{
+    vec2 pos = (coord - area[side].xy) / area[side].zw;
+    bool inside = all(greaterThanEqual(pos, vec2(0.0))) && all(lessThanEqual(pos, vec2(1.0)));
+    // Repeating and mirroring is done by the sampler
+    if (inside || extend[side] == 1 || extend[side] == 2) {
+        return textureLod(image, pos, lod[side].x);
+    } else if (extend[side] == 3) {
+        vec2 blurred = (coord - cover[side].xy) / cover[side].zw;
+        return vec4(textureLod(image, blurred, lod[side].y).rgb * background[side].rgb, 1.0);
+    }
+    return background[side];
+}
+
+vec4 fromColor(vec2 coord)
+{
+    return place(from, coord, 0);
+}
+
+vec4 toColor(vec2 coord)
+{
+    return place(to, coord, 1);
+}
+
+// Offset in [-0.5, 0.5) of the current pixel
+float threshold()
+{
+    if (dither == 1) {
+        // 8x8 Bayer matrix, interleaving the bits of the position in reverse
+        ivec2 pos = ivec2(gl_FragCoord.xy) & 7;
+        int x = pos.x ^ pos.y;
+        int value = ((x & 1) << 5) | ((pos.y & 1) << 4) | ((x & 2) << 2)
+            | ((pos.y & 2) << 1) | ((x & 4) >> 1) | ((pos.y & 4) >> 2);
+        return (float(value) + 0.5) / 64.0 - 0.5;
+    } else if (dither == 2) {
+        ivec2 size = textureSize(noise, 0);
+        return texelFetch(noise, ivec2(gl_FragCoord.xy) % size, 0).r - 0.5;
+    }
+    return 0.0;
+}
 
 void main()
 {
-    vec4 colFrom = texture(from, Texcoord);
-    vec4 colTo = texture(to, Texcoord);
-    outColor = mix(colFrom, colTo, ratio);
+BODY
+    outColor += texture(backdrop, Texcoord) * (1.0 - outColor.a);
+    outColor.rgb += threshold() / 255.0;
+}
+\0";
+
+// Solid colors and gradients, the position is taken in pixels from the center
+// of the output. Rows are counted from the top as the result is used as texture.
+const FILLSHADER: &[u8] = b"#version 150 core
+out vec4 outColor;
+
+uniform vec2 size;
+uniform bool radial;
+uniform vec2 direction;
+uniform int count;
+uniform vec3 colors[16];
+uniform float offsets[16];
+
+void main()
+{
+    vec2 pos = gl_FragCoord.xy - size / 2.0;
+    float progress;
+    if (radial) {
+        progress = length(pos) / length(size / 2.0);
+    } else {
+        // The gradient line reaches the corners, as in CSS
+        float len = abs(size.x * direction.x) + abs(size.y * direction.y);
+        progress = dot(pos, direction) / len + 0.5;
+    }
+    vec3 color = colors[0];
+    for (int i = 1; i < count; i++) {
+        float span = max(offsets[i] - offsets[i - 1], 0.00001);
+        color = mix(color, colors[i], clamp((progress - offsets[i - 1]) / span, 0.0, 1.0));
+    }
+    outColor = vec4(color, 1.0);
 }
 \0";
 
@@ -59,19 +149,35 @@ impl Shader {
         unsafe { Self::new(VSHADER, gl::VERTEX_SHADER) }
     }
 
-    pub fn new_fragment() -> Self {
-        unsafe { Self::new(FRAGSHADER, gl::FRAGMENT_SHADER) }
+    pub fn new_fragment(kind: TransitionKind) -> Self {
+        let src = FRAGSHADER.replace("BODY", &kind.body());
+        unsafe { Self::new(src.as_bytes(), gl::FRAGMENT_SHADER) }
+    }
+
+    pub fn new_fill_fragment() -> Self {
+        unsafe { Self::new(FILLSHADER, gl::FRAGMENT_SHADER) }
     }
 
     unsafe fn new(src: &[u8], kind: GLenum) -> Self {
         let shader = gl::CreateShader(kind);
         let src = std::ffi::CStr::from_bytes_with_nul_unchecked(src).as_ptr();
-        gl::ShaderSource(shader, 1, (&[src]).as_ptr(), std::ptr::null());
+        gl::ShaderSource(shader, 1, [src].as_ptr(), std::ptr::null());
         gl::CompileShader(shader);
         let mut status = 0i32;
         gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
-        if status != gl::TRUE.into() {
-            error!("Shader did not compile.");
+        if status != i32::from(gl::TRUE) {
+            let mut log = [0u8; 1024];
+            let mut length = 0;
+            gl::GetShaderInfoLog(
+                shader,
+                log.len() as i32,
+                &mut length,
+                log.as_mut_ptr() as *mut gl::types::GLchar,
+            );
+            error!(
+                "Shader did not compile: {}",
+                String::from_utf8_lossy(&log[..length as usize])
+            );
             std::process::exit(1);
         }
         check_error("Shader Creation");
@@ -0,0 +1,130 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+/// The effect used to blend from one image to the next, taken from the `type` of a transition.
+///
+/// Gnome only knows `overlay`, all other kinds are specific to enkei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TransitionKind {
+    #[default]
+    Overlay,
+    Dissolve,
+    WipeLeft,
+    WipeRight,
+    WipeUp,
+    WipeDown,
+    Slide,
+    Zoom,
+    Circle,
+}
+
+// Every transition has to show exactly `from` at a ratio of 0 and exactly `to` at a ratio of 1,
+// static images are drawn with a ratio of 0 regardless of the active transition.
+const OVERLAY: &str = "
+    outColor = mix(fromColor(Texcoord), toColor(Texcoord), ratio);
+";
+
+const DISSOLVE: &str = "
+    float noise = fract(sin(dot(floor(gl_FragCoord.xy / 2.0), vec2(12.9898, 78.233))) * 43758.5453);
+    outColor = noise < ratio ? toColor(Texcoord) : fromColor(Texcoord);
+";
+
+// The edge travels from one side to the other, softened over a small band
+const WIPE: &str = "
+    float edge = 1.0 - ratio * (1.0 + SOFTNESS);
+    float amount = smoothstep(edge, edge + SOFTNESS, POSITION);
+    outColor = mix(fromColor(Texcoord), toColor(Texcoord), amount);
+";
+
+const SLIDE: &str = "
+    if (Texcoord.x < 1.0 - ratio) {
+        outColor = fromColor(vec2(Texcoord.x + ratio, Texcoord.y));
+    } else {
+        outColor = toColor(vec2(Texcoord.x - 1.0 + ratio, Texcoord.y));
+    }
+";
+
+const ZOOM: &str = "
+    vec4 colFrom = fromColor((Texcoord - 0.5) / (1.0 + ratio) + 0.5);
+    outColor = mix(colFrom, toColor(Texcoord), ratio);
+";
+
+// The circle is corrected for the aspect ratio of the output and grows until all corners are covered
+const CIRCLE: &str = "
+    vec2 scale = resolution / max(resolution.x, resolution.y);
+    float dist = length((Texcoord - 0.5) * scale);
+    float radius = ratio * (length(0.5 * scale) + SOFTNESS);
+    float amount = 1.0 - smoothstep(radius - SOFTNESS, radius, dist);
+    outColor = mix(fromColor(Texcoord), toColor(Texcoord), amount);
+";
+
+impl TransitionKind {
+    pub const ALL: [TransitionKind; 9] = [
+        TransitionKind::Overlay,
+        TransitionKind::Dissolve,
+        TransitionKind::WipeLeft,
+        TransitionKind::WipeRight,
+        TransitionKind::WipeUp,
+        TransitionKind::WipeDown,
+        TransitionKind::Slide,
+        TransitionKind::Zoom,
+        TransitionKind::Circle,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.trim().to_lowercase().as_str() {
+            "overlay" | "fade" => TransitionKind::Overlay,
+            "dissolve" => TransitionKind::Dissolve,
+            "wipe-left" => TransitionKind::WipeLeft,
+            "wipe-right" => TransitionKind::WipeRight,
+            "wipe-up" => TransitionKind::WipeUp,
+            "wipe-down" => TransitionKind::WipeDown,
+            "slide" => TransitionKind::Slide,
+            "zoom" => TransitionKind::Zoom,
+            "circle" => TransitionKind::Circle,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransitionKind::Overlay => "overlay",
+            TransitionKind::Dissolve => "dissolve",
+            TransitionKind::WipeLeft => "wipe-left",
+            TransitionKind::WipeRight => "wipe-right",
+            TransitionKind::WipeUp => "wipe-up",
+            TransitionKind::WipeDown => "wipe-down",
+            TransitionKind::Slide => "slide",
+            TransitionKind::Zoom => "zoom",
+            TransitionKind::Circle => "circle",
+        }
+    }
+
+    /// Body of the `main` function of the fragment shader realizing this transition.
+    pub fn body(&self) -> String {
+        match self {
+            TransitionKind::Overlay => OVERLAY.into(),
+            TransitionKind::Dissolve => DISSOLVE.into(),
+            TransitionKind::WipeLeft => WIPE.replace("POSITION", "Texcoord.x"),
+            TransitionKind::WipeRight => WIPE.replace("POSITION", "(1.0 - Texcoord.x)"),
+            TransitionKind::WipeUp => WIPE.replace("POSITION", "Texcoord.y"),
+            TransitionKind::WipeDown => WIPE.replace("POSITION", "(1.0 - Texcoord.y)"),
+            TransitionKind::Slide => SLIDE.into(),
+            TransitionKind::Zoom => ZOOM.into(),
+            TransitionKind::Circle => CIRCLE.into(),
+        }
+    }
+}
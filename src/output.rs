@@ -14,22 +14,33 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{outputs::ScaledMode, ApplicationError};
+use crate::{
+    image::{
+        scaling::{Filter, Placement},
+        Original, ScaledImage, Timing,
+    },
+    outputs::ScaledMode,
+    ApplicationError,
+};
 
 use super::outputs::Output;
+use enkei::fill::Fill;
 use khronos_egl::{Context as eglContext, Display as eglDisplay, Surface as eglSurface};
 use log::debug;
 use std::sync::mpsc::channel;
 use wayland_egl::WlEglSurface;
 
-use super::opengl::context::Context as glContext;
+use super::opengl::context::{Context as glContext, SharedImage};
+use super::opengl::dither::Dither;
+use super::opengl::transition::TransitionKind;
 
 use super::egl;
 use wayland_client::{
-    protocol::{wl_compositor::WlCompositor, wl_surface::WlSurface},
+    protocol::{wl_callback, wl_compositor::WlCompositor, wl_surface::WlSurface},
     EventQueue, Main,
 };
 
+use std::cell::Cell;
 use std::rc::Rc;
 use std::sync::RwLock;
 
@@ -42,7 +53,7 @@ use wayland_protocols::wlr::unstable::layer_shell::v1::client::zwlr_layer_surfac
 
 #[derive(Debug)]
 pub struct OutputRendering {
-    pub output: Rc<RwLock<Output>>,
+    _output: Rc<RwLock<Output>>,
     output_id: u32,
     surface: Main<WlSurface>,
     egl_context: eglContext,
@@ -50,8 +61,55 @@ pub struct OutputRendering {
     _wl_egl_surface: WlEglSurface,
     egl_surface: eglSurface,
     gl_context: glContext,
+    transition: TransitionKind,
+    playback: Playback,
     pub resolution: ScaledMode,
-    pub scale: i32,
+}
+
+/// Progress of the animated images shown on an output.
+#[derive(Debug)]
+struct Playback {
+    from: Timing,
+    to: Timing,
+    // Seconds the animations have been played, this does not advance while
+    // the output is hidden
+    position: f64,
+    loops: Option<u32>,
+    // Ratio of the last drawn transition and the frames shown in it
+    ratio: Cell<f32>,
+    frames: Cell<(usize, usize)>,
+    // The compositor only notifies about presented frames if the surface is visible
+    presented: Rc<Cell<bool>>,
+}
+
+impl Playback {
+    fn new(loops: Option<u32>) -> Self {
+        Self {
+            from: Timing::still(),
+            to: Timing::still(),
+            position: 0.0,
+            loops,
+            ratio: Cell::new(0.0),
+            frames: Cell::new((0, 0)),
+            presented: Rc::new(Cell::new(true)),
+        }
+    }
+
+    fn frames(&self) -> (usize, usize) {
+        (
+            self.from.frame_at(self.position, self.loops),
+            self.to.frame_at(self.position, self.loops),
+        )
+    }
+}
+
+/// Settings of the rendering which are the same for all outputs.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    pub loops: Option<u32>,
+    pub dither: Dither,
+    /// Context whose textures are shared with the contexts of all outputs.
+    pub share: Option<eglContext>,
 }
 
 impl OutputRendering {
@@ -61,6 +119,7 @@ impl OutputRendering {
         event_queue: &mut EventQueue,
         output: Rc<RwLock<Output>>,
         egl_display: eglDisplay,
+        options: RenderOptions,
     ) -> Result<Self, ApplicationError> {
         let lock = output
             .read()
@@ -125,7 +184,7 @@ impl OutputRendering {
         );
         let wl_egl_surface =
             wayland_egl::WlEglSurface::new(&surface, scaled_mode.width, scaled_mode.height);
-        let (egl_context, egl_config) = create_context(egl_display)?;
+        let (egl_context, egl_config) = create_context(egl_display, options.share)?;
         let egl_surface = unsafe {
             egl.create_window_surface(
                 egl_display,
@@ -146,7 +205,7 @@ impl OutputRendering {
             .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
         surface.commit();
         // Rendering with the `gl` bindings are all unsafe let's block this away
-        let context = super::opengl::context::Context::new();
+        let context = super::opengl::context::Context::new(options.dither, &scaled_mode);
         // Make the buffer the current one
         egl.swap_buffers(egl_display, egl_surface)
             .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
@@ -160,12 +219,12 @@ impl OutputRendering {
             .sync_roundtrip(&mut (), |_, _, _| { /* we ignore unfiltered messages */ })
             .map_err(|e| ApplicationError::io_error(e, line!(), file!()))?;
 
-        surface.damage(0, 0, i32::max_value(), i32::max_value());
+        surface.damage(0, 0, i32::MAX, i32::MAX);
         surface.commit();
 
         drop(lock);
         Ok(OutputRendering {
-            output,
+            _output: output,
             output_id,
             surface,
             egl_context,
@@ -173,12 +232,90 @@ impl OutputRendering {
             egl_display,
             egl_surface,
             gl_context: context,
+            transition: TransitionKind::default(),
+            playback: Playback::new(options.loops),
             resolution: scaled_mode,
-            scale,
         })
     }
 
-    pub fn set_to(&mut self, image: &[u8], mode: &ScaledMode) -> Result<(), ApplicationError> {
+    pub fn set_to(
+        &mut self,
+        image: &ScaledImage,
+        mode: &ScaledMode,
+    ) -> Result<(), ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        self.gl_context
+            .set_to(&image.frames, mode.width, mode.height);
+        self.playback.to = image.timing.clone();
+        Ok(())
+    }
+
+    /// Set the image shown first, animations restart from their first frame.
+    pub fn set_from(
+        &mut self,
+        image: &ScaledImage,
+        mode: &ScaledMode,
+    ) -> Result<(), ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        self.gl_context
+            .set_from(&image.frames, mode.width, mode.height);
+        self.playback.from = image.timing.clone();
+        self.playback.position = 0.0;
+        Ok(())
+    }
+
+    /// Upload an image at its own size, `None` if it cannot be placed by the GPU.
+    /// The texture is available in all contexts sharing with this one.
+    pub fn upload(&self, original: &Original) -> Result<Option<Rc<SharedImage>>, ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        Ok(SharedImage::new(original).map(Rc::new))
+    }
+
+    /// Set an uploaded image as the one shown first, it is placed on the
+    /// output while drawing.
+    pub fn set_from_shared(
+        &mut self,
+        image: Rc<SharedImage>,
+        placement: &Placement,
+        filter: Filter,
+    ) -> Result<(), ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        self.playback.from = image.timing.clone();
+        self.playback.position = 0.0;
+        self.gl_context.set_from_shared(image, placement, filter);
+        Ok(())
+    }
+
+    pub fn set_to_shared(
+        &mut self,
+        image: Rc<SharedImage>,
+        placement: &Placement,
+        filter: Filter,
+    ) -> Result<(), ApplicationError> {
         egl.make_current(
             self.egl_display,
             Some(self.egl_surface),
@@ -186,11 +323,13 @@ impl OutputRendering {
             Some(self.egl_context),
         )
         .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
-        self.gl_context.set_to(image, mode.width, mode.height);
+        self.playback.to = image.timing.clone();
+        self.gl_context.set_to_shared(image, placement, filter);
         Ok(())
     }
 
-    pub fn set_from(&mut self, image: &[u8], mode: &ScaledMode) -> Result<(), ApplicationError> {
+    /// Draw a color or gradient as image shown first.
+    pub fn set_from_fill(&mut self, fill: &Fill) -> Result<(), ApplicationError> {
         egl.make_current(
             self.egl_display,
             Some(self.egl_surface),
@@ -198,14 +337,91 @@ impl OutputRendering {
             Some(self.egl_context),
         )
         .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
-        self.gl_context.set_from(image, mode.width, mode.height);
+        self.gl_context
+            .fill_from(fill, self.resolution.width, self.resolution.height);
+        self.playback.from = Timing::still();
+        self.playback.position = 0.0;
         Ok(())
     }
 
+    pub fn set_to_fill(&mut self, fill: &Fill) -> Result<(), ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        self.gl_context
+            .fill_to(fill, self.resolution.width, self.resolution.height);
+        self.playback.to = Timing::still();
+        Ok(())
+    }
+
+    /// Show the image below transparent parts of the wallpaper.
+    pub fn set_backdrop(
+        &mut self,
+        image: &ScaledImage,
+        mode: &ScaledMode,
+    ) -> Result<(), ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        self.gl_context
+            .set_backdrop(&image.frames, mode.width, mode.height);
+        Ok(())
+    }
+
+    pub fn set_backdrop_fill(&mut self, fill: &Fill) -> Result<(), ApplicationError> {
+        egl.make_current(
+            self.egl_display,
+            Some(self.egl_surface),
+            Some(self.egl_surface),
+            Some(self.egl_context),
+        )
+        .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
+        self.gl_context
+            .fill_backdrop(fill, self.resolution.width, self.resolution.height);
+        Ok(())
+    }
+
+    /// Select the effect used when drawing the upcoming transition.
+    pub fn set_transition(&mut self, kind: TransitionKind) {
+        self.transition = kind;
+    }
+
     pub fn output_id(&self) -> u32 {
         self.output_id
     }
 
+    /// The shortest time a frame of the shown animations is visible, `None`
+    /// if only still images are shown or all animations have ended.
+    pub fn frame_interval(&self) -> Option<f64> {
+        [&self.playback.from, &self.playback.to]
+            .into_iter()
+            .filter(|timing| timing.is_playing(self.playback.position, self.playback.loops))
+            .map(Timing::shortest)
+            .reduce(f64::min)
+    }
+
+    /// Advance the shown animations by `elapsed` seconds and redraw if a new
+    /// frame is due. Playback pauses while the last frame has not been
+    /// presented, which is the case while the output is covered or off.
+    pub fn advance(&mut self, elapsed: f64) -> Result<(), ApplicationError> {
+        if !self.playback.presented.get() {
+            return Ok(());
+        }
+        self.playback.position += elapsed;
+        if self.playback.frames() != self.playback.frames.get() {
+            self.draw(self.playback.ratio.get())?;
+        }
+        Ok(())
+    }
+
     pub fn draw(&self, process: f32) -> Result<(), ApplicationError> {
         egl.make_current(
             self.egl_display,
@@ -214,11 +430,21 @@ impl OutputRendering {
             Some(self.egl_context),
         )
         .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
-        self.gl_context.draw(process);
+        let frames = self.playback.frames();
+        self.gl_context.draw(process, self.transition, frames);
+        self.playback.ratio.set(process);
+        self.playback.frames.set(frames);
+        // Requested before swapping, as this commits the surface
+        let presented = Rc::clone(&self.playback.presented);
+        presented.set(false);
+        self.surface.frame().quick_assign(move |_, event, _| {
+            if let wl_callback::Event::Done { .. } = event {
+                presented.set(true);
+            }
+        });
         egl.swap_buffers(self.egl_display, self.egl_surface)
             .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
-        self.surface
-            .damage(0, 0, i32::max_value(), i32::max_value());
+        self.surface.damage(0, 0, i32::MAX, i32::MAX);
         self.surface.commit();
         Ok(())
     }
@@ -234,7 +460,11 @@ impl OutputRendering {
     }
 }
 
-fn create_context(display: egl::Display) -> Result<(egl::Context, egl::Config), ApplicationError> {
+/// Create an OpenGL context, sharing textures with `share` if given.
+pub fn create_context(
+    display: egl::Display,
+    share: Option<egl::Context>,
+) -> Result<(egl::Context, egl::Config), ApplicationError> {
     let attributes = [
         egl::RED_SIZE,
         8,
@@ -261,7 +491,7 @@ fn create_context(display: egl::Display) -> Result<(egl::Context, egl::Config),
     ];
 
     let context = egl
-        .create_context(display, config, None, &context_attributes)
+        .create_context(display, config, share, &context_attributes)
         .map_err(|e| ApplicationError::egl_error(e, line!(), file!()))?;
 
     Ok((context, config))
@@ -14,4 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+pub mod apple_desktop;
+pub mod gnome_properties;
 pub mod gnome_xml;
+pub mod schedule;
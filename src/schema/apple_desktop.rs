@@ -0,0 +1,52 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Property lists embedded into the XMP metadata of macOS dynamic desktops.
+// Indices refer to the top level images of the HEIC container.
+use serde::Deserialize;
+
+/// Content of `apple_desktop:h24`, images bound to a time of day.
+#[derive(Debug, Deserialize)]
+pub struct H24 {
+    #[serde(rename = "ti")]
+    pub images: Vec<TimedImage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimedImage {
+    #[serde(rename = "i")]
+    pub index: usize,
+    /// Fraction of the day from midnight, between 0 and 1.
+    #[serde(rename = "t")]
+    pub time: f64,
+}
+
+/// Content of `apple_desktop:solar`, images bound to a position of the sun.
+#[derive(Debug, Deserialize)]
+pub struct Solar {
+    #[serde(rename = "si")]
+    pub images: Vec<SolarImage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SolarImage {
+    #[serde(rename = "i")]
+    pub index: usize,
+    #[serde(rename = "a")]
+    pub altitude: f64,
+    #[serde(rename = "z")]
+    pub azimuth: f64,
+}
@@ -0,0 +1,39 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Entries of `gnome-background-properties`, which list the wallpapers shown in
+// the settings of Gnome. Since Gnome 42 an entry may carry a dark variant.
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct Wallpapers {
+    #[serde(rename = "wallpaper", default)]
+    pub wallpapers: Vec<Wallpaper>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Wallpaper {
+    pub name: Option<String>,
+    #[serde(rename = "@deleted", default)]
+    pub deleted: bool,
+    pub filename: PathBuf,
+    #[serde(rename = "filename-dark")]
+    pub filename_dark: Option<PathBuf>,
+    /// How the wallpaper is placed, one of Gnome's picture options like `zoom` or `centered`.
+    pub options: Option<String>,
+}
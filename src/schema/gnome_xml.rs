@@ -1,14 +1,18 @@
 use std::path::PathBuf;
 
-use serde::Deserialize;
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Deserialize)]
+// Attributes carry the `@` prefix and text content is `$text`, following quick-xml.
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Background {
     #[serde(rename = "$value")]
     pub images: Vec<Image>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub enum Image {
     #[serde(rename = "starttime")]
     StartTime {
@@ -20,13 +24,112 @@ pub enum Image {
         second: u32,
     },
     #[serde(rename = "static")]
-    Static { duration: f64, file: PathBuf },
+    Static { duration: f64, file: File },
     #[serde(rename = "transition")]
     Transition {
-        #[serde(rename = "type")]
+        #[serde(rename = "@type")]
         kind: String,
         duration: f64,
-        from: PathBuf,
-        to: PathBuf,
+        from: File,
+        to: File,
+    },
+    /// Not part of the Gnome format, images shown at a certain position of the sun.
+    #[serde(rename = "solar")]
+    Solar {
+        file: File,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        elevation: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        azimuth: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        phase: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transition: Option<f64>,
     },
 }
+
+/// A file is either given as a plain path or as a list of variants for different resolutions.
+///
+/// ```xml
+/// <file>
+///   <size width="1920" height="1080">/usr/share/backgrounds/1080p.jpg</size>
+///   <size width="3840" height="2160">/usr/share/backgrounds/4k.jpg</size>
+/// </file>
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct File {
+    pub path: Option<PathBuf>,
+    pub sizes: Vec<Size>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Size {
+    #[serde(rename = "@width")]
+    pub width: u32,
+    #[serde(rename = "@height")]
+    pub height: u32,
+    #[serde(rename = "$text")]
+    pub path: PathBuf,
+}
+
+// Both forms share the same element, derived implementations cannot tell them apart
+impl<'de> Deserialize<'de> for File {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FileVisitor)
+    }
+}
+
+impl Serialize for File {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.path {
+            Some(path) if self.sizes.is_empty() => path.serialize(serializer),
+            _ => {
+                let mut file = serializer.serialize_struct("file", 2)?;
+                file.serialize_field("size", &self.sizes)?;
+                if let Some(path) = &self.path {
+                    file.serialize_field("$text", path)?;
+                }
+                file.end()
+            }
+        }
+    }
+}
+
+struct FileVisitor;
+
+impl<'de> Visitor<'de> for FileVisitor {
+    type Value = File;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a path or a list of <size> elements")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<File, E> {
+        Ok(File {
+            path: Some(value.trim().into()),
+            sizes: Vec::new(),
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<File, A::Error> {
+        let mut file = File {
+            path: None,
+            sizes: Vec::new(),
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "size" => file.sizes.push(map.next_value()?),
+                "$text" => {
+                    let value: String = map.next_value()?;
+                    if !value.trim().is_empty() {
+                        file.path = Some(value.trim().into());
+                    }
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(file)
+    }
+}
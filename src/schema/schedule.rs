@@ -0,0 +1,67 @@
+// The native format of enkei, images are bound to a time of day. It can be
+// written in TOML or JSON, e.g.
+//
+// [[anchor]]
+// time = "07:00"
+// file = "morning.jpg"
+// transition = 1800
+// easing = "sine-inout"
+//
+// Instead of anchors, sets of wallpapers may be bound to dates. The first set
+// matching the current date is shown, anchors given as well apply on all other
+// dates.
+//
+// [[set]]
+// months = ["dec", "jan", "feb"]
+// file = "winter.toml"
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct Schedule {
+    #[serde(rename = "anchor", alias = "anchors", default)]
+    pub anchors: Vec<Anchor>,
+    #[serde(rename = "set", alias = "sets", default)]
+    pub sets: Vec<Set>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Anchor {
+    /// Local time of day formatted as `HH:MM` or `HH:MM:SS` at which the image is fully shown.
+    pub time: String,
+    pub file: PathBuf,
+    /// Length of the blend into the next image in seconds, ending when the next anchor is reached.
+    pub transition: Option<f64>,
+    pub easing: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    /// Scaling of this image, replacing `--scale`.
+    pub scale: Option<String>,
+    /// Alignment of this image, replacing `--align`.
+    pub align: Option<String>,
+}
+
+/// A wallpaper shown on certain dates, all given conditions have to be met.
+#[derive(Debug, Deserialize)]
+pub struct Set {
+    /// Any wallpaper, e.g. a description, an image or a directory.
+    pub file: PathBuf,
+    /// Names, abbreviations or numbers of months, e.g. `"dec"` or `"12"`.
+    #[serde(default)]
+    pub months: Vec<String>,
+    /// Meteorological seasons, e.g. `"winter"` for December to February.
+    #[serde(default)]
+    pub seasons: Vec<String>,
+    /// Names or abbreviations of weekdays, e.g. `"sat"`.
+    #[serde(default)]
+    pub weekdays: Vec<String>,
+    /// First day of a range given as `MM-DD` repeating every year, or as `YYYY-MM-DD`.
+    pub from: Option<String>,
+    /// Last day of the range, in the same form as `from`.
+    pub until: Option<String>,
+    /// Scaling of all images of the wallpaper, replacing `--scale`.
+    pub scale: Option<String>,
+    /// Alignment of all images of the wallpaper, replacing `--align`.
+    pub align: Option<String>,
+}
@@ -15,5 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 mod resource_loader;
+mod virtual_clock;
 
 pub use resource_loader::*;
+pub use virtual_clock::*;
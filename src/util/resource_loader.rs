@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 use crate::outputs::ScaledMode;
@@ -22,37 +23,77 @@ use log::debug;
 
 use crate::image::{
     error::ImageError,
-    scaling::{Filter, Scaling},
-    Image,
+    scaling::{Filter, Placement},
+    Image, Original, ScaledImage,
 };
 
 use cached::stores::SizedCache;
 use cached::Cached;
 
 pub struct ResourceLoader {
+    // Skip the correction of orientation and colors of loaded images
+    as_stored: bool,
     last_loaded: SizedCache<PathBuf, Image>,
-    scaled: SizedCache<(PathBuf, ScaledMode, Filter, Scaling), Vec<u8>>,
+    // Images larger than textures may be, these are placed by the CPU instead
+    too_large: HashSet<PathBuf>,
+    scaled: SizedCache<(PathBuf, ScaledMode, Filter, Placement), ScaledImage>,
 }
 
 impl ResourceLoader {
-    pub fn new() -> Self {
+    pub fn new(as_stored: bool) -> Self {
         Self {
+            as_stored,
             last_loaded: SizedCache::with_size(2),
+            too_large: HashSet::new(),
             scaled: SizedCache::with_size(2),
         }
     }
 
+    /// Decode the image at `path` without scaling it, `None` for vector images
+    /// and images marked as too large for textures.
+    ///
+    /// Images uploaded to the GPU are kept there, so they are not cached here
+    /// and do not push out the images which are placed by the CPU. Vector
+    /// images are cached as they are rendered for each output next.
+    pub fn original(&mut self, path: &PathBuf) -> Result<Option<Original>, ImageError> {
+        if self.too_large.contains(path) {
+            return Ok(None);
+        }
+        if let Some(image) = self.last_loaded.cache_get(path) {
+            return Ok(image.original());
+        }
+        let image = Image::new(
+            path.clone(),
+            Placement::default(),
+            Filter::Good,
+            self.as_stored,
+        )?;
+        let original = image.original();
+        if original.is_none() {
+            debug!("Caching image {{ path: {:?} }}", path);
+            self.last_loaded.cache_set(path.clone(), image);
+        }
+        Ok(original)
+    }
+
+    /// Remember that the image at `path` could not be uploaded, so it is only
+    /// decoded once more when it is placed by the CPU.
+    pub fn mark_too_large(&mut self, path: &PathBuf) {
+        debug!("Image too large for textures {{ path: {:?} }}", path);
+        self.too_large.insert(path.clone());
+    }
+
     pub fn load(
         &mut self,
         path: &PathBuf,
         mode: &ScaledMode,
-        scaling: Scaling,
+        placement: Placement,
         filter: Filter,
-    ) -> Result<&Vec<u8>, ImageError> {
-        let scale_key = (path.clone(), mode.clone(), filter, scaling);
+    ) -> Result<&ScaledImage, ImageError> {
+        let scale_key = (path.clone(), mode.clone(), filter, placement);
         // workaround as this introduces nastier non-lexical lifetimes
         if self.scaled.cache_get(&scale_key).is_some() {
-            // The scaling and filter cannot differ
+            // The placement and filter cannot differ
             debug!(
                 "Fetching scaled image from cache {{ path: {:?}, mode: {:?} }}",
                 path, mode
@@ -61,18 +102,18 @@ impl ResourceLoader {
         }
 
         if self.last_loaded.cache_get(path).is_none() {
-            let surface = Image::new(path.clone(), scaling, filter)?;
+            let surface = Image::new(path.clone(), placement, filter, self.as_stored)?;
             debug!("Caching image {{ path: {:?} }}", path);
             self.last_loaded.cache_set(path.clone(), surface);
         }
 
         let surface = self.last_loaded.cache_get_mut(path).expect("Cannot fail");
-        // Update the scaling and the filter to get the proper ratio for the new
+        // Update the placement and the filter to get the proper ratio for the new
         // image
-        surface.scaling = scaling;
+        surface.placement = placement;
         surface.filter = filter;
         let surface_scaled = surface.process(mode)?;
         self.scaled.cache_set(scale_key.clone(), surface_scaled);
-        return Ok(self.scaled.cache_get(&scale_key).expect("Cannot fail"));
+        Ok(self.scaled.cache_get(&scale_key).expect("Cannot fail"))
     }
 }
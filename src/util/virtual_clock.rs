@@ -0,0 +1,95 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// The time shown by the wallpaper may differ from the real time, to preview
+// other times of day or to watch a whole cycle sped up.
+
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::localize;
+
+/// A jump of the virtual clock.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Seek {
+    /// Seconds since midnight of today in local time
+    Time(u32),
+    /// Seconds relative to the real time
+    Offset(i64),
+}
+
+impl Seek {
+    pub fn time(time: NaiveTime) -> Self {
+        use chrono::Timelike;
+        Seek::Time(time.num_seconds_from_midnight())
+    }
+
+    fn target(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Seek::Time(secs) => {
+                let today = now.with_timezone(&Local).date().naive_local();
+                let time = NaiveTime::from_num_seconds_from_midnight(secs % 86400, 0);
+                localize(&today.and_time(time))
+            }
+            Seek::Offset(secs) => now + Duration::seconds(*secs),
+        }
+    }
+}
+
+/// Clock from which the wallpaper takes the current time.
+///
+/// Virtual time passes `speed` times as fast as real time, starting from the
+/// moment of the last seek or change of speed.
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualClock {
+    real_anchor: DateTime<Utc>,
+    virtual_anchor: DateTime<Utc>,
+    speed: f64,
+}
+
+impl VirtualClock {
+    pub fn new(seek: Option<Seek>, speed: f64) -> Self {
+        let now = Utc::now();
+        Self {
+            real_anchor: now,
+            virtual_anchor: seek.map(|seek| seek.target(now)).unwrap_or(now),
+            speed,
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        let real = (Utc::now() - self.real_anchor).num_milliseconds() as f64;
+        self.virtual_anchor + Duration::milliseconds((real * self.speed) as i64)
+    }
+
+    pub fn seek(&mut self, seek: Seek) {
+        let now = Utc::now();
+        self.real_anchor = now;
+        self.virtual_anchor = seek.target(now);
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        self.virtual_anchor = self.now();
+        self.real_anchor = Utc::now();
+        self.speed = speed;
+    }
+
+    /// The real time it takes for `secs` seconds of virtual time to pass.
+    pub fn real_duration(&self, secs: f64) -> std::time::Duration {
+        std::time::Duration::try_from_secs_f64(secs / self.speed)
+            .unwrap_or(std::time::Duration::MAX)
+    }
+}
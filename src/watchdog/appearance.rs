@@ -0,0 +1,147 @@
+// enkei: An OpenGL accelerated wallpaper tool for wayland
+// Copyright (C) 2022 Johannes Wünsche
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Follows the color scheme of the desktop for wallpapers with a light and a dark variant.
+// The XDG desktop portal announces every change of the scheme, desktops without
+// the portal are followed by a single `gsettings monitor` instead.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+
+use log::{debug, info, warn};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedValue, Value};
+
+use crate::messages::WorkerMessage;
+use crate::metadata::Appearance;
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const PORTAL_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+const GNOME_SCHEMA: &str = "org.gnome.desktop.interface";
+
+pub fn initialize(sender: Sender<WorkerMessage>) {
+    debug!("Initializing Appearance Watchdog");
+    std::thread::spawn(move || {
+        if let Err(e) = watch_portal(&sender) {
+            debug!(
+                "Desktop portal is not available, falling back to gsettings {{ error: {} }}",
+                e
+            );
+            if let Err(e) = watch_gsettings(&sender) {
+                warn!("Cannot follow the color scheme of the desktop: {}", e);
+            }
+        }
+    });
+}
+
+/// The color scheme preferred by the desktop, `None` if it cannot be determined.
+pub fn system_appearance() -> Option<Appearance> {
+    Connection::session()
+        .and_then(|connection| settings(&connection))
+        .and_then(|proxy| portal_appearance(&proxy))
+        .ok()
+        .or_else(gsettings_appearance)
+}
+
+fn notify(sender: &Sender<WorkerMessage>, appearance: Appearance) {
+    info!("Detected color scheme {:?}.", appearance);
+    sender
+        .send(WorkerMessage::AppearanceChanged(appearance))
+        .expect("Appearance Watchdog could not bark!");
+}
+
+fn settings(connection: &Connection) -> zbus::Result<Proxy<'static>> {
+    Proxy::new(
+        connection,
+        PORTAL_DESTINATION,
+        PORTAL_PATH,
+        PORTAL_INTERFACE,
+    )
+}
+
+fn portal_appearance(proxy: &Proxy) -> zbus::Result<Appearance> {
+    // `ReadOne` exists since version 2 of the interface, the deprecated `Read` wraps the value once more
+    let value: OwnedValue = proxy
+        .call("ReadOne", &(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY))
+        .or_else(|_| proxy.call("Read", &(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY)))?;
+    appearance(&value).ok_or_else(|| zbus::Error::Failure("Unexpected color scheme".into()))
+}
+
+fn appearance(value: &Value) -> Option<Appearance> {
+    match value {
+        Value::Value(inner) => appearance(inner),
+        // 1 prefers dark, 0 states no preference and 2 prefers light
+        Value::U32(1) => Some(Appearance::Dark),
+        Value::U32(_) => Some(Appearance::Light),
+        _ => None,
+    }
+}
+
+// Blocks for as long as the session bus is connected
+fn watch_portal(sender: &Sender<WorkerMessage>) -> zbus::Result<()> {
+    let connection = Connection::session()?;
+    let proxy = settings(&connection)?;
+    // Only listen if the portal actually knows the color scheme
+    portal_appearance(&proxy)?;
+    for signal in proxy.receive_signal("SettingChanged")? {
+        let (namespace, key, value): (String, String, OwnedValue) = signal.body().deserialize()?;
+        if namespace != APPEARANCE_NAMESPACE || key != COLOR_SCHEME_KEY {
+            continue;
+        }
+        if let Some(appearance) = appearance(&value) {
+            notify(sender, appearance);
+        }
+    }
+    Ok(())
+}
+
+// The value is printed quoted, e.g. `'prefer-dark'`
+fn scheme(value: &str) -> Appearance {
+    if value.contains("prefer-dark") {
+        Appearance::Dark
+    } else {
+        Appearance::Light
+    }
+}
+
+fn gsettings_appearance() -> Option<Appearance> {
+    let output = Command::new("gsettings")
+        .args(["get", GNOME_SCHEMA, COLOR_SCHEME_KEY])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| scheme(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Each change is printed as a line like `color-scheme: 'prefer-dark'`
+fn watch_gsettings(sender: &Sender<WorkerMessage>) -> std::io::Result<()> {
+    let mut child = Command::new("gsettings")
+        .args(["monitor", GNOME_SCHEMA, COLOR_SCHEME_KEY])
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take().expect("Output of gsettings is piped");
+    for line in BufReader::new(stdout).lines() {
+        notify(sender, scheme(&line?));
+    }
+    child.wait()?;
+    Ok(())
+}
@@ -1,12 +1,15 @@
 use std::{io::Read, os::unix::net::UnixListener, path::PathBuf};
 
-use log::{debug, error, warn};
+use enkei::fill::Fill;
+use log::{debug, warn};
 use serde::Deserialize;
 use std::sync::mpsc::Sender;
 
 use crate::{
-    image::scaling::{Filter, Scaling},
+    image::scaling::{Alignment, Background, Filter, Scaling},
     messages::WorkerMessage,
+    metadata::Source,
+    util::Seek,
     Mode,
 };
 use thiserror::Error;
@@ -23,8 +26,15 @@ enum InnerError {
 pub struct Message {
     pub filter: Option<Filter>,
     pub scaling: Option<Scaling>,
-    pub path: PathBuf,
+    pub path: Option<PathBuf>,
     pub mode: Option<Mode>,
+    pub seek: Option<Seek>,
+    pub speed: Option<f64>,
+    pub color: Option<String>,
+    pub alignment: Option<Alignment>,
+    pub tile_scale: Option<f64>,
+    pub background: Option<String>,
+    pub backdrop: Option<String>,
 }
 
 // Spawn an additional thread solely for receiving messages from `enkeictl`
@@ -51,14 +61,41 @@ fn spawn_inner(tx: Sender<WorkerMessage>) -> Result<(), InnerError> {
             let mut res = Vec::new();
             socket.read_to_end(&mut res).ok();
             if let Ok(msg) = bincode::deserialize::<Message>(&res) {
-                if msg.path.exists() && msg.path.is_file() {
-                    debug!("Received path {{ {:?} }}", msg.path);
+                let valid_path = msg
+                    .path
+                    .as_ref()
+                    .is_none_or(|path| path.is_file() || path.is_dir());
+                let valid_speed = msg.speed.is_none_or(|speed| speed > 0.0);
+                let valid_tile_scale = msg.tile_scale.is_none_or(|scale| scale > 0.0);
+                let valid_background = msg
+                    .background
+                    .as_ref()
+                    .is_none_or(|background| background.parse::<Background>().is_ok());
+                let valid_color = msg
+                    .color
+                    .as_ref()
+                    .is_none_or(|color| color.parse::<Fill>().is_ok());
+                let valid_backdrop = msg
+                    .backdrop
+                    .as_ref()
+                    .is_none_or(|backdrop| backdrop.parse::<Source>().is_ok());
+                if valid_path
+                    && valid_speed
+                    && valid_color
+                    && valid_tile_scale
+                    && valid_background
+                    && valid_backdrop
+                {
+                    debug!(
+                        "Received message {{ path: {:?}, seek: {:?}, speed: {:?}, color: {:?} }}",
+                        msg.path, msg.seek, msg.speed, msg.color
+                    );
                     tx.send(WorkerMessage::IPCConfigUpdate(msg))
                         .expect("Cannot fail");
                 } else {
                     debug!(
-                        "Received a message {{ {:?} }} but it was no valid path. Dropping...",
-                        msg.path
+                        "Received a message {{ path: {:?}, speed: {:?}, color: {:?}, tile_scale: {:?}, background: {:?}, backdrop: {:?} }} but it was no valid path, speed, color, tile scale, background or backdrop. Dropping...",
+                        msg.path, msg.speed, msg.color, msg.tile_scale, msg.background, msg.backdrop
                     )
                 }
             }
@@ -10,16 +10,38 @@ const POLL_INTERVAL_SEC: u64 = 60;
 
 pub fn initialize(sender: Sender<WorkerMessage>) {
     debug!("Initializing Sleep Watchdog");
+    let mut zone = iana_time_zone::get_timezone().ok();
     std::thread::spawn(move || loop {
         let start = Local::now();
         std::thread::sleep(std::time::Duration::from_secs(POLL_INTERVAL_SEC));
         let end = Local::now();
+        // The cached zone of the C library is only reloaded by the worker, so a new zone is detected by name
+        let current = iana_time_zone::get_timezone().ok();
         debug!("Elapsed sleeping time {}s", (end - start).num_seconds());
         if (end - start).num_seconds() > POLL_INTERVAL_SEC as i64 {
             info!("Detected Sleeping Cycle. Send Refresh to worker thread.");
             sender
                 .send(WorkerMessage::Refresh)
                 .expect("Sleeping Watchdog could not bark!");
+        } else if start.offset() != end.offset() {
+            // Timers have been set up for the old offset, which is wrong for anything following the wall clock
+            info!(
+                "Detected change of UTC offset from {} to {}. Send Refresh to worker thread.",
+                start.offset(),
+                end.offset()
+            );
+            sender
+                .send(WorkerMessage::Refresh)
+                .expect("Sleeping Watchdog could not bark!");
+        } else if current != zone {
+            info!(
+                "Detected change of time zone from {:?} to {:?}. Send Refresh to worker thread.",
+                zone, current
+            );
+            sender
+                .send(WorkerMessage::Refresh)
+                .expect("Sleeping Watchdog could not bark!");
         }
+        zone = current;
     });
 }
@@ -50,6 +50,24 @@ pub fn spawn_animation_ticker(
     });
 }
 
+pub fn spawn_frame_ticker(
+    interval: std::time::Duration,
+    tx: Sender<WorkerMessage>,
+    rx: Receiver<()>,
+) {
+    debug!(
+        "Spawning Frame Ticker {{ interval: {}s }}",
+        interval.as_secs_f32()
+    );
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+        if rx.try_recv().is_ok() {
+            break;
+        }
+        tx.send(WorkerMessage::FrameTick).expect(ERROR_MSG);
+    });
+}
+
 pub fn spawn_simple_timer(
     duration: std::time::Duration,
     tx: Sender<WorkerMessage>,
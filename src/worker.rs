@@ -17,19 +17,25 @@
 use std::sync::mpsc::{channel, Receiver, Sender};
 
 use std::collections::hash_map::Entry;
+use std::path::PathBuf;
 use std::rc::Rc;
 
-use log::debug;
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
 use wayland_client::{Display, EventQueue, GlobalManager};
 
 use wayland_client::protocol::wl_compositor;
 use wayland_protocols::wlr::unstable::layer_shell::v1::client::zwlr_layer_shell_v1::ZwlrLayerShellV1;
 
-use crate::image::scaling::{Filter, Scaling};
+use crate::image::scaling::{Filter, Placement};
 
 use crate::messages::WorkerMessage;
-use crate::metadata::{AnimationState, Metadata};
-use crate::util::ResourceLoader;
+use crate::metadata::{
+    AnimationState, Easing, Metadata, MetadataReader, Preference, Settings, Source,
+};
+use crate::opengl::context::SharedImage;
+use crate::opengl::dither::Dither;
+use crate::util::{ResourceLoader, VirtualClock};
 use crate::watchdog::timer;
 use crate::{ApplicationError, Args};
 use std::collections::HashMap;
@@ -37,11 +43,27 @@ use std::collections::HashMap;
 pub struct State {
     fps: f64,
     ticker_active: bool,
+    // Stops the ticker advancing animated images
+    frame_ticker: Option<Sender<()>>,
+    // Time of the last frame tick, animations advance by the time in between
+    last_frame: std::time::Instant,
+    max_fps: Option<f64>,
+    loops: Option<u32>,
+    dither: Dither,
+    // Easing of the currently running transition
+    easing: Easing,
     renders: HashMap<u32, OutputRendering>,
     timers: TimerCollection,
     metadata: Metadata,
-    scale: Scaling,
+    settings: Settings,
+    // Time shown by the wallpaper, may be shifted or sped up
+    clock: VirtualClock,
+    placement: Placement,
     filter: Filter,
+    backdrop: Option<Source>,
+    // Images uploaded once and placed by the GPU on all outputs, `None` if
+    // images are placed by the CPU
+    textures: Option<HashMap<PathBuf, Rc<SharedImage>>>,
 }
 
 struct TimerCollection {
@@ -70,21 +92,68 @@ impl TimerCollection {
 }
 
 impl State {
-    fn new(metadata: Metadata, scale: Scaling, filter: Filter) -> Self {
+    fn new(metadata: Metadata, args: &Args) -> Self {
         Self {
             fps: 1f64,
             ticker_active: false,
+            frame_ticker: None,
+            last_frame: std::time::Instant::now(),
+            max_fps: args.max_fps,
+            loops: args.loops,
+            dither: args.dither,
+            easing: Easing::default(),
             renders: HashMap::new(),
             timers: TimerCollection::new(),
             metadata,
-            scale,
-            filter,
+            settings: args.settings(),
+            clock: args.virtual_clock(),
+            placement: args.placement(),
+            filter: args.filter,
+            backdrop: args.backdrop.clone(),
+            textures: args.gpu_scaling.then(HashMap::new),
+        }
+    }
+
+    /// Release the textures of images no output shows anymore.
+    fn prune_textures(&mut self) {
+        if let Some(textures) = &mut self.textures {
+            textures.retain(|_, image| Rc::strong_count(image) > 1);
         }
     }
 
     fn set_fps(&mut self, new: f64) {
         self.fps = f64::max(self.fps, new);
     }
+
+    /// Start advancing animated images if any output shows one.
+    fn start_frame_ticker(&mut self, senders: Sender<WorkerMessage>) {
+        if self.frame_ticker.is_some() {
+            return;
+        }
+        let interval = self
+            .renders
+            .values()
+            .filter_map(OutputRendering::frame_interval)
+            .reduce(f64::min);
+        if let Some(interval) = interval {
+            let max_fps = self.max_fps.unwrap_or(self.fps);
+            let (tx, rx) = channel();
+            timer::spawn_frame_ticker(
+                std::time::Duration::from_secs_f64(interval.max(1.0 / max_fps)),
+                senders,
+                rx,
+            );
+            self.last_frame = std::time::Instant::now();
+            self.frame_ticker = Some(tx);
+        }
+    }
+
+    fn stop_frame_ticker(&mut self) {
+        if let Some(ticker) = self.frame_ticker.take() {
+            // The ticker may have stopped already
+            ticker.send(()).ok();
+        }
+    }
 }
 
 pub fn work(
@@ -112,13 +181,22 @@ pub fn work(
     let egl_display = setup_egl(&display)?;
 
     // Use an output independent store for loaded images, allows for some reduction in IO time
-    let mut resource_loader = ResourceLoader::new();
-    let mut state = State::new(metadata, args.scale, args.filter);
+    let mut resource_loader = ResourceLoader::new(args.as_stored);
+    let mut state = State::new(metadata, &args);
+    // Textures are created in the contexts of the outputs, this context only
+    // keeps them shared while outputs come and go
+    let share = match state.textures {
+        Some(_) => Some(create_context(egl_display, None)?.0),
+        None => None,
+    };
 
     // Spawn IPC socket
     crate::watchdog::ipc::spawn(senders.clone());
     // Initialize Watchdogs for Suspension Cycles
     crate::watchdog::sleeping::initialize(senders.clone());
+    if state.settings.preference == Preference::System {
+        crate::watchdog::appearance::initialize(senders.clone());
+    }
 
     // Process all pending requests
     loop {
@@ -157,24 +235,41 @@ pub fn work(
                             &mut event_queue,
                             Rc::clone(&output),
                             egl_display,
+                            RenderOptions {
+                                loops: state.loops,
+                                dither: state.dither,
+                                share,
+                            },
                         )?);
                         let output = state.renders.get_mut(&id).expect("Cannot fail");
-                        let animation_state = state.metadata.current()?;
-                        refresh_output(
+                        if let Some(backdrop) = &state.backdrop {
+                            set_backdrop(output, &mut resource_loader, backdrop, state.filter)?;
+                        }
+                        let now = state.clock.now();
+                        let animation_state = state.metadata.current_at(now)?;
+                        state.easing = animation_state.transition().easing();
+                        let result = refresh_output(
                             output,
                             &mut resource_loader,
+                            &mut state.textures,
                             &animation_state,
-                            state.scale,
+                            state.placement,
                             state.filter,
-                        )?;
+                        );
+                        if skip_unreadable(result, &mut state.metadata, now)? {
+                            senders.send(WorkerMessage::Refresh).expect("Cannot fail");
+                            continue;
+                        }
                         state.ticker_active = state_draw(
                             &animation_state,
                             output,
                             state.ticker_active,
                             state.fps,
+                            &state.clock,
                             senders.clone(),
                             state.timers.new_timer_channel(),
                         )?;
+                        state.start_frame_ticker(senders.clone());
                     } else {
                         debug!("Output {{ id: {id} }} updated and not new. Refreshing.");
                         // On Change we have to reinitialize the output, though this needs
@@ -201,7 +296,7 @@ pub fn work(
                     debug!("Message: AnimationStep {{ process: {} }}", process);
                     for (id, output) in state.renders.iter() {
                         debug!("Drawing on WlOutput {{ id: {} }}", id);
-                        output.draw(ezing::quad_inout(process))?;
+                        output.draw(state.easing.apply(process))?;
                     }
                     if process >= 1.0 {
                         senders
@@ -211,38 +306,72 @@ pub fn work(
                 }
                 WorkerMessage::AnimationStart(duration) => {
                     debug!("Message: AnimationStart {{ duration: {}s }}", duration);
-                    let count = calc_frame_updates(duration, state.fps);
+                    let real = state.clock.real_duration(duration);
+                    let count = calc_frame_updates(real.as_secs_f64(), state.fps);
                     timer::spawn_animation_ticker(
-                        std::time::Duration::from_secs_f64(duration / count),
+                        real.div_f64(count),
                         count as u64,
                         0,
                         senders.clone(),
                         state.timers.new_timer_channel(),
                     );
                 }
+                WorkerMessage::FrameTick => {
+                    let elapsed = state.last_frame.elapsed().as_secs_f64();
+                    state.last_frame = std::time::Instant::now();
+                    for output in state.renders.values_mut() {
+                        output.advance(elapsed)?;
+                    }
+                    // Animations played as often as requested stay at their last frame
+                    if state
+                        .renders
+                        .values()
+                        .all(|output| output.frame_interval().is_none())
+                    {
+                        state.stop_frame_ticker();
+                    }
+                }
                 WorkerMessage::Refresh => {
                     debug!("Message: Refresh");
                     let start = std::time::Instant::now();
                     state.ticker_active = false;
+                    state.stop_frame_ticker();
                     state.timers.cancel_timers();
-                    let animation_state = state.metadata.current()?;
+                    crate::metadata::reload_time_zone();
+                    let now = state.clock.now();
+                    state.metadata.rescan(now);
+                    let animation_state = state.metadata.current_at(now)?;
+                    state.easing = animation_state.transition().easing();
+                    let mut skipped = false;
                     for (_, output) in state.renders.iter_mut() {
-                        refresh_output(
+                        let result = refresh_output(
                             output,
                             &mut resource_loader,
+                            &mut state.textures,
                             &animation_state,
-                            state.scale,
+                            state.placement,
                             state.filter,
-                        )?;
+                        );
+                        if skip_unreadable(result, &mut state.metadata, now)? {
+                            skipped = true;
+                            break;
+                        }
                         state.ticker_active = state_draw(
                             &animation_state,
                             output,
                             state.ticker_active,
                             state.fps,
+                            &state.clock,
                             senders.clone(),
                             state.timers.new_timer_channel(),
                         )?;
                     }
+                    if skipped {
+                        senders.send(WorkerMessage::Refresh).expect("Cannot fail");
+                        continue;
+                    }
+                    state.prune_textures();
+                    state.start_frame_ticker(senders.clone());
                     debug!(
                         "Refreshing of all outputs took {}ms",
                         start.elapsed().as_millis()
@@ -250,15 +379,54 @@ pub fn work(
                 }
                 WorkerMessage::IPCConfigUpdate(msg) => {
                     debug!("IPC Config update");
-                    state.metadata = crate::get_metadata_for_path(&msg.mode, &msg.path)?;
+                    if let Some(path) = msg.path {
+                        state.metadata =
+                            crate::get_metadata_for_path(&msg.mode, &path, &state.settings)?;
+                    }
+                    if let Some(fill) = msg.color.and_then(|color| color.parse().ok()) {
+                        state.metadata = MetadataReader::static_source(Source::fill(fill));
+                    }
+                    if let Some(seek) = msg.seek {
+                        state.clock.seek(seek);
+                    }
+                    if let Some(speed) = msg.speed {
+                        state.clock.set_speed(speed);
+                    }
                     if let Some(val) = msg.scaling {
-                        state.scale = val;
+                        state.placement.scaling = val;
+                    }
+                    if let Some(val) = msg.alignment {
+                        state.placement.alignment = val;
+                    }
+                    if let Some(val) = msg.tile_scale {
+                        state.placement.tile_scale = val;
+                    }
+                    if let Some(val) = msg.background.and_then(|spec| spec.parse().ok()) {
+                        state.placement.background = val;
                     }
                     if let Some(val) = msg.filter {
                         state.filter = val;
                     }
+                    if let Some(val) = msg.backdrop.and_then(|spec| spec.parse().ok()) {
+                        for output in state.renders.values_mut() {
+                            set_backdrop(output, &mut resource_loader, &val, state.filter)?;
+                        }
+                        state.backdrop = Some(val);
+                    }
                     senders.send(WorkerMessage::Refresh).expect("Cannot fail");
                 }
+                WorkerMessage::AppearanceChanged(appearance) => {
+                    debug!(
+                        "Message: AppearanceChanged {{ appearance: {:?} }}",
+                        appearance
+                    );
+                    if state
+                        .metadata
+                        .set_system_appearance(appearance, state.clock.now())
+                    {
+                        senders.send(WorkerMessage::Refresh).expect("Cannot fail");
+                    }
+                }
             }
         }
     }
@@ -273,6 +441,7 @@ fn state_draw(
     output: &mut OutputRendering,
     mut ticker_active: bool,
     fps: f64,
+    clock: &VirtualClock,
     senders: Sender<WorkerMessage>,
     recv: Receiver<()>,
 ) -> Result<bool, ApplicationError> {
@@ -284,7 +453,7 @@ fn state_draw(
             );
             if transition.is_animated() && !ticker_active {
                 timer::spawn_simple_timer(
-                    std::time::Duration::from_secs_f64(transition.duration_static() - progress),
+                    clock.real_duration(transition.duration_static() - progress),
                     senders,
                     recv,
                     WorkerMessage::AnimationStart(transition.duration_transition()),
@@ -292,7 +461,7 @@ fn state_draw(
                 ticker_active = true;
             } else if !ticker_active {
                 timer::spawn_simple_timer(
-                    std::time::Duration::from_secs_f64(transition.duration_static() - progress),
+                    clock.real_duration(transition.duration_static() - progress),
                     senders,
                     recv,
                     WorkerMessage::Refresh,
@@ -305,15 +474,21 @@ fn state_draw(
         AnimationState::Transition(progress, transition) => {
             // This state is always animated
             debug!(
-                "Current state is dynamic {{ duration_transition: {}, progress: {progress} }}",
+                "Current state is dynamic {{ kind: {:?}, duration_transition: {}, progress: {progress} }}",
+                transition.kind(),
                 transition.duration_transition()
             );
-            let count = calc_frame_updates(transition.duration_transition(), fps);
+            let count = calc_frame_updates(
+                clock
+                    .real_duration(transition.duration_transition())
+                    .as_secs_f64(),
+                fps,
+            );
             let step = transition.duration_transition() / count;
             let finished = progress / step;
             if !ticker_active {
                 timer::spawn_animation_ticker(
-                    std::time::Duration::from_secs_f64(step),
+                    clock.real_duration(step),
                     count as u64,
                     finished as u64,
                     senders,
@@ -321,50 +496,140 @@ fn state_draw(
                 );
                 ticker_active = true;
             }
-            output.draw((finished / count) as f32)?;
+            output.draw(transition.easing().apply((finished / count) as f32))?;
             Ok(ticker_active)
         }
     }
 }
 
-fn refresh_output(
+// Backdrops cover the whole output, they only change with the output or on request
+fn set_backdrop(
     output: &mut OutputRendering,
     resources: &mut ResourceLoader,
-    metadata: &AnimationState,
-    scaling: Scaling,
+    backdrop: &Source,
     filter: Filter,
 ) -> Result<(), ApplicationError> {
-    let transition = {
-        match metadata {
-            AnimationState::Static(_, t) => t,
-            AnimationState::Transition(_, t) => t,
+    match backdrop.as_fill() {
+        Some(fill) => output.set_backdrop_fill(fill),
+        None => {
+            let scaled_mode = output.resolution.clone();
+            let image = resources.load(
+                backdrop.best_for(&scaled_mode),
+                &scaled_mode,
+                Placement::default(),
+                filter,
+            )?;
+            output.set_backdrop(image, &scaled_mode)
+        }
+    }
+}
+
+// Slideshows leave out images which cannot be loaded, returns whether the outputs have to be refreshed again
+fn skip_unreadable(
+    result: Result<(), ApplicationError>,
+    metadata: &mut Metadata,
+    now: DateTime<Utc>,
+) -> Result<bool, ApplicationError> {
+    match result {
+        Err(ApplicationError::UnreadableImage(path, _, e)) if metadata.skip(&path, now) => {
+            warn!("Skipping image {:?} which cannot be loaded: {}", path, e);
+            Ok(true)
         }
+        result => result.map(|_| false),
+    }
+}
+
+// Shared images are uploaded by the first output showing them, `None` if the
+// image has to be placed by the CPU, as vector images and images too large for
+// textures are
+fn shared_image(
+    output: &OutputRendering,
+    resources: &mut ResourceLoader,
+    textures: &mut Option<HashMap<PathBuf, Rc<SharedImage>>>,
+    path: &PathBuf,
+    entry: Option<&str>,
+) -> Result<Option<Rc<SharedImage>>, ApplicationError> {
+    let Some(textures) = textures else {
+        return Ok(None);
     };
+    if let Some(image) = textures.get(path) {
+        return Ok(Some(Rc::clone(image)));
+    }
+    let original = resources
+        .original(path)
+        .map_err(|e| ApplicationError::unreadable(path, entry, e))?;
+    let Some(original) = original else {
+        return Ok(None);
+    };
+    let image = output.upload(&original)?;
+    match &image {
+        Some(image) => {
+            debug!("Uploaded shared image {{ path: {:?} }}", path);
+            textures.insert(path.clone(), Rc::clone(image));
+        }
+        None => resources.mark_too_large(path),
+    }
+    Ok(image)
+}
+
+fn refresh_output(
+    output: &mut OutputRendering,
+    resources: &mut ResourceLoader,
+    textures: &mut Option<HashMap<PathBuf, Rc<SharedImage>>>,
+    metadata: &AnimationState,
+    placement: Placement,
+    filter: Filter,
+) -> Result<(), ApplicationError> {
+    let transition = metadata.transition();
     let scaled_mode = output.resolution.clone();
 
-    let from = resources.load(transition.from(), &scaled_mode, scaling, filter)?;
     let start = std::time::Instant::now();
-    output.set_from(from, &scaled_mode)?;
+    let from = transition.from();
+    match from.as_fill() {
+        Some(fill) => output.set_from_fill(fill)?,
+        None => {
+            let path = from.best_for(&scaled_mode);
+            match shared_image(output, resources, textures, path, from.entry())? {
+                Some(image) => output.set_from_shared(image, &from.placement(placement), filter)?,
+                None => {
+                    let image = resources
+                        .load(path, &scaled_mode, from.placement(placement), filter)
+                        .map_err(|e| ApplicationError::unreadable(path, from.entry(), e))?;
+                    output.set_from(image, &scaled_mode)?;
+                }
+            }
+        }
+    }
     debug!(
         "Sending of image texture to shader took {}ms",
         start.elapsed().as_millis()
     );
-    if transition.is_animated() {
-        let to = resources.load(
-            transition.to().expect("Cannot fail."),
-            &scaled_mode,
-            scaling,
-            filter,
-        )?;
-        output.set_to(to, &scaled_mode)?;
+    output.set_transition(transition.kind().unwrap_or_default());
+    let to = if transition.is_animated() {
+        transition.to().expect("Cannot fail.")
     } else {
-        output.set_to(from, &scaled_mode)?;
+        from
+    };
+    match to.as_fill() {
+        Some(fill) => output.set_to_fill(fill)?,
+        None => {
+            let path = to.best_for(&scaled_mode);
+            match shared_image(output, resources, textures, path, to.entry())? {
+                Some(image) => output.set_to_shared(image, &to.placement(placement), filter)?,
+                None => {
+                    let image = resources
+                        .load(path, &scaled_mode, to.placement(placement), filter)
+                        .map_err(|e| ApplicationError::unreadable(path, to.entry(), e))?;
+                    output.set_to(image, &scaled_mode)?;
+                }
+            }
+        }
     }
     Ok(())
 }
 
 use crate::egl;
-use crate::output::OutputRendering;
+use crate::output::{create_context, OutputRendering, RenderOptions};
 
 fn setup_egl(display: &Display) -> Result<egl::Display, ApplicationError> {
     egl.bind_api(egl::OPENGL_API)